@@ -0,0 +1,62 @@
+//! Benchmarks for the default cooldown store, filled with cooldowns of 100k users
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use poise::serenity_prelude as serenity;
+use poise::{CooldownBucket, CooldownStore, InMemoryCooldownStore};
+use std::time::{Duration, SystemTime};
+
+/// Number of users with an active cooldown
+const NUM_USERS: u64 = 100_000;
+
+/// Creates a store in which every user has an active per-user cooldown on the `ping` command
+fn filled_store(runtime: &tokio::runtime::Runtime) -> InMemoryCooldownStore {
+    let store = InMemoryCooldownStore::new();
+    let until = SystemTime::now() + Duration::from_secs(3600);
+    runtime.block_on(async {
+        for user_id in 0..NUM_USERS {
+            let bucket = CooldownBucket::User(serenity::UserId(user_id));
+            store.set("ping", bucket, until).await;
+        }
+    });
+    store
+}
+
+fn cooldown_store(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let store = filled_store(&runtime);
+
+    c.bench_function("get (100k users, hit)", |b| {
+        let bucket = CooldownBucket::User(serenity::UserId(NUM_USERS / 2));
+        b.iter(|| runtime.block_on(store.get("ping", bucket)))
+    });
+    c.bench_function("get (100k users, miss)", |b| {
+        let bucket = CooldownBucket::User(serenity::UserId(NUM_USERS + 1));
+        b.iter(|| runtime.block_on(store.get("ping", bucket)))
+    });
+    c.bench_function("set (100k users)", |b| {
+        let bucket = CooldownBucket::User(serenity::UserId(NUM_USERS / 2));
+        let until = SystemTime::now() + Duration::from_secs(3600);
+        b.iter(|| runtime.block_on(store.set("ping", bucket, until)))
+    });
+    c.bench_function("purge (100k users, all expired)", |b| {
+        b.iter_batched(
+            || {
+                let store = InMemoryCooldownStore::new();
+                runtime.block_on(async {
+                    for user_id in 0..NUM_USERS {
+                        let bucket = CooldownBucket::User(serenity::UserId(user_id));
+                        store.set("ping", bucket, SystemTime::UNIX_EPOCH).await;
+                    }
+                });
+                store
+            },
+            |store| runtime.block_on(store.purge()),
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+criterion_group!(benches, cooldown_store);
+criterion_main!(benches);
@@ -98,3 +98,20 @@ pub async fn add(
 
     Ok(())
 }
+
+/// Roll a die. Each user can roll 5 times per minute, plus 2 extra rolls in quick succession
+#[poise::command(
+    prefix_command,
+    slash_command,
+    user_cooldown = 60,
+    user_cooldown_uses = 5,
+    user_cooldown_burst = 2
+)]
+pub async fn roll(ctx: Context<'_>) -> Result<(), Error> {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .subsec_nanos();
+    ctx.say(format!("You rolled a {}", nanos % 6 + 1)).await?;
+
+    Ok(())
+}
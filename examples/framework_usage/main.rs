@@ -23,6 +23,7 @@ async fn help(
     ctx: Context<'_>,
     #[description = "Specific command to show help about"]
     #[autocomplete = "poise::builtins::autocomplete_command"]
+    #[rest]
     command: Option<String>,
 ) -> Result<(), Error> {
     poise::builtins::help(
@@ -90,6 +91,7 @@ async fn main() {
             checks::delete(),
             checks::ferrisparty(),
             checks::add(),
+            checks::roll(),
             poise::Command {
                 subcommands: vec![
                     subcommands::child1(),
@@ -107,6 +109,7 @@ async fn main() {
                 poise::Prefix::Literal("hey bot"),
                 poise::Prefix::Literal("hey bot,"),
             ],
+            help_command: Some("help".into()),
             ..Default::default()
         },
         /// The global error handler for all error cases that may occur
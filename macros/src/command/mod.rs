@@ -36,6 +36,29 @@ impl<T> Default for List<T> {
     }
 }
 
+/// A pair of values, like the locale and translation in `name_localized("ja", "...")`
+#[derive(Debug)]
+pub struct Tuple2<T>(pub T, pub T);
+impl<T: darling::FromMeta> darling::FromMeta for Tuple2<T> {
+    fn from_list(items: &[::syn::NestedMeta]) -> darling::Result<Self> {
+        match items {
+            [a, b] => Ok(Self(T::from_nested_meta(a)?, T::from_nested_meta(b)?)),
+            _ => Err(darling::Error::custom("expected exactly two arguments")),
+        }
+    }
+}
+
+/// Generates a `HashMap` expression from a list of (locale, translation) pairs
+fn localizations_to_tokens(localizations: &[Tuple2<String>]) -> proc_macro2::TokenStream {
+    let locales = localizations.iter().map(|Tuple2(locale, _)| locale);
+    let translations = localizations
+        .iter()
+        .map(|Tuple2(_, translation)| translation);
+    quote::quote! {
+        vec![ #( (#locales, #translations) ),* ].into_iter().collect()
+    }
+}
+
 /// Representation of the command attribute arguments (`#[command(...)]`)
 #[derive(Default, Debug, darling::FromMeta)]
 #[darling(default)]
@@ -56,11 +79,17 @@ pub struct CommandArgs {
     discard_spare_arguments: bool,
     hide_in_help: bool,
     ephemeral: bool,
+    auto_defer: Option<bool>,
     required_permissions: Option<syn::punctuated::Punctuated<syn::Ident, syn::Token![|]>>,
     required_bot_permissions: Option<syn::punctuated::Punctuated<syn::Ident, syn::Token![|]>>,
     owners_only: bool,
+    requires_voice: bool,
     identifying_name: Option<String>,
     category: Option<String>,
+    #[darling(multiple)]
+    name_localized: Vec<Tuple2<String>>,
+    #[darling(multiple)]
+    description_localized: Vec<Tuple2<String>>,
 
     // In seconds
     global_cooldown: Option<u64>,
@@ -68,6 +97,16 @@ pub struct CommandArgs {
     guild_cooldown: Option<u64>,
     channel_cooldown: Option<u64>,
     member_cooldown: Option<u64>,
+    global_cooldown_uses: Option<u32>,
+    user_cooldown_uses: Option<u32>,
+    guild_cooldown_uses: Option<u32>,
+    channel_cooldown_uses: Option<u32>,
+    member_cooldown_uses: Option<u32>,
+    global_cooldown_burst: Option<u32>,
+    user_cooldown_burst: Option<u32>,
+    guild_cooldown_burst: Option<u32>,
+    channel_cooldown_burst: Option<u32>,
+    member_cooldown_burst: Option<u32>,
 }
 
 /// Representation of the function parameter attribute arguments
@@ -83,6 +122,10 @@ struct ParamArgs {
     lazy: bool,
     flag: bool,
     rest: bool,
+    #[darling(multiple)]
+    name_localized: Vec<Tuple2<String>>,
+    #[darling(multiple)]
+    description_localized: Vec<Tuple2<String>>,
 }
 
 /// Part of the Invocation struct. Represents a single parameter of a Discord command.
@@ -249,7 +292,9 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
     let command_name = &inv.command_name;
     let context_menu_name = wrap_option(inv.args.context_menu_command.as_ref());
 
+    let name_localizations = localizations_to_tokens(&inv.args.name_localized);
     let description = wrap_option(inv.description.as_ref());
+    let description_localizations = localizations_to_tokens(&inv.args.description_localized);
     let hide_in_help = &inv.args.hide_in_help;
     let category = wrap_option(inv.args.category.as_ref());
 
@@ -258,10 +303,21 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
     let guild_cooldown = wrap_option(inv.args.guild_cooldown);
     let channel_cooldown = wrap_option(inv.args.channel_cooldown);
     let member_cooldown = wrap_option(inv.args.member_cooldown);
+    let global_cooldown_uses = wrap_option(inv.args.global_cooldown_uses);
+    let user_cooldown_uses = wrap_option(inv.args.user_cooldown_uses);
+    let guild_cooldown_uses = wrap_option(inv.args.guild_cooldown_uses);
+    let channel_cooldown_uses = wrap_option(inv.args.channel_cooldown_uses);
+    let member_cooldown_uses = wrap_option(inv.args.member_cooldown_uses);
+    let global_cooldown_burst = inv.args.global_cooldown_burst.unwrap_or(0);
+    let user_cooldown_burst = inv.args.user_cooldown_burst.unwrap_or(0);
+    let guild_cooldown_burst = inv.args.guild_cooldown_burst.unwrap_or(0);
+    let channel_cooldown_burst = inv.args.channel_cooldown_burst.unwrap_or(0);
+    let member_cooldown_burst = inv.args.member_cooldown_burst.unwrap_or(0);
 
     let required_permissions = &inv.required_permissions;
     let required_bot_permissions = &inv.required_bot_permissions;
     let owners_only = inv.args.owners_only;
+    let requires_voice = inv.args.requires_voice;
 
     let explanation = match &inv.args.explanation_fn {
         Some(explanation_fn) => quote::quote! { Some(#explanation_fn) },
@@ -288,6 +344,10 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
 
     let parameters = slash::generate_parameters(&inv)?;
     let ephemeral = inv.args.ephemeral;
+    let auto_defer = match inv.args.auto_defer {
+        Some(auto_defer) => quote::quote! { Some(#auto_defer) },
+        None => quote::quote! { None },
+    };
 
     let function_name = std::mem::replace(&mut inv.function.sig.ident, syn::parse_quote! { inner });
     let function_visibility = &inv.function.vis;
@@ -306,23 +366,36 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
 
                 subcommands: Vec::new(),
                 name: #command_name,
+                name_localizations: #name_localizations,
                 qualified_name: String::from(#command_name), // properly filled in later by Framework
                 identifying_name: String::from(#identifying_name),
                 category: #category,
                 inline_help: #description,
+                description_localizations: #description_localizations,
                 multiline_help: #explanation,
                 hide_in_help: #hide_in_help,
-                cooldowns: std::sync::Mutex::new(::poise::Cooldowns::new(::poise::CooldownConfig {
+                cooldowns: ::poise::Cooldowns::new(::poise::CooldownConfig {
                     global: #global_cooldown.map(std::time::Duration::from_secs),
                     user: #user_cooldown.map(std::time::Duration::from_secs),
                     guild: #guild_cooldown.map(std::time::Duration::from_secs),
                     channel: #channel_cooldown.map(std::time::Duration::from_secs),
                     member: #member_cooldown.map(std::time::Duration::from_secs),
-                })),
+                    global_uses: #global_cooldown_uses,
+                    user_uses: #user_cooldown_uses,
+                    guild_uses: #guild_cooldown_uses,
+                    channel_uses: #channel_cooldown_uses,
+                    member_uses: #member_cooldown_uses,
+                    global_burst: #global_cooldown_burst,
+                    user_burst: #user_cooldown_burst,
+                    guild_burst: #guild_cooldown_burst,
+                    channel_burst: #channel_cooldown_burst,
+                    member_burst: #member_cooldown_burst,
+                }),
                 reuse_response: #reuse_response,
                 required_permissions: #required_permissions,
                 required_bot_permissions: #required_bot_permissions,
                 owners_only: #owners_only,
+                requires_voice: #requires_voice,
                 check: #check,
                 on_error: #on_error,
                 parameters: vec![ #( #parameters ),* ],
@@ -333,6 +406,7 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
 
                 context_menu_name: #context_menu_name,
                 ephemeral: #ephemeral,
+                auto_defer: #auto_defer,
             }
         }
     })
@@ -1,4 +1,4 @@
-use super::{wrap_option, Invocation};
+use super::{localizations_to_tokens, wrap_option, Invocation};
 use syn::spanned::Spanned as _;
 
 // ngl this is ugly
@@ -32,6 +32,7 @@ pub fn generate_parameters(inv: &Invocation) -> Result<Vec<proc_macro2::TokenStr
         }
         let description = wrap_option(param.args.description.as_ref());
 
+        let variadic = extract_type_parameter("Vec", &param.type_).is_some();
         let (mut required, type_) = match extract_type_parameter("Option", &param.type_)
             .or_else(|| extract_type_parameter("Vec", &param.type_))
         {
@@ -100,26 +101,29 @@ pub fn generate_parameters(inv: &Invocation) -> Result<Vec<proc_macro2::TokenStr
             None => quote::quote! { None },
         };
 
-        parameter_structs.push((
-            quote::quote! {
-                ::poise::CommandParameter {
-                    name: stringify!(#param_name),
-                    description: #description,
-                    required: #required,
-                    channel_types: #channel_types,
-                    type_setter: #type_setter,
-                    autocomplete_callback: #autocomplete_callback,
-                }
-            },
-            required,
-        ));
+        let name_localizations = localizations_to_tokens(&param.args.name_localized);
+        let description_localizations = localizations_to_tokens(&param.args.description_localized);
+        let rest = param.args.rest;
+        let flag = param.args.flag;
+        parameter_structs.push(quote::quote! {
+            ::poise::CommandParameter {
+                name: stringify!(#param_name),
+                name_localizations: #name_localizations,
+                description: #description,
+                description_localizations: #description_localizations,
+                required: #required,
+                variadic: #variadic,
+                rest: #rest,
+                flag: #flag,
+                channel_types: #channel_types,
+                type_setter: #type_setter,
+                autocomplete_callback: #autocomplete_callback,
+            }
+        });
     }
-    // Sort the parameters so that optional parameters come last - Discord requires this order
-    parameter_structs.sort_by_key(|(_, required)| !required);
-    Ok(parameter_structs
-        .into_iter()
-        .map(|(builder, _)| builder)
-        .collect::<Vec<_>>())
+    // Parameters are kept in declaration order, which is the order prefix commands parse them in.
+    // Discord's requirement that optional parameters come last is handled when registering
+    Ok(parameter_structs)
 }
 
 pub fn generate_slash_action(inv: &Invocation) -> proc_macro2::TokenStream {
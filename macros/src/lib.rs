@@ -1,4 +1,5 @@
 mod command;
+mod modal;
 mod slash_choice_parameter;
 
 use proc_macro::TokenStream;
@@ -31,16 +32,25 @@ for example for command-specific help (i.e. `~help command_name`). Escape newlin
 - `hide_in_help`: Hide this command in help menus
 - `ephemeral`: Make bot responses ephemeral if possible
     - Only poise's function, like `poise::send_reply`, respect this preference
+- `auto_defer`: Defer the response if the command doesn't respond in time (see `FrameworkOptions::auto_defer`). Use `auto_defer = false` to opt out when enabled globally (slash-only)
 - `required_permissions`: Permissions which the command caller needs to have
 - `required_bot_permissions`: Permissions which the bot is known to need
 - `owners_only`: Restricts command callers to a configurable list of owners (see FrameworkOptions)
+- `requires_voice`: Only allows command callers who are connected to a voice channel in the guild
 - `identifying_name`: Optionally, a unique identifier for this command for your personal usage
 - `category`: Category of this command which affects placement in the help command
+- `name_localized("locale", "name")`: Localized command name for the given locale (e.g. `ja`). Can be repeated for multiple locales (slash-only)
+- `description_localized("locale", "description")`: Localized command description for the given locale. Can be repeated for multiple locales (slash-only)
 - `global_cooldown`: Minimum duration between invocations, globally
 - `user_cooldown`: Minimum duration between invocations, per user
 - `guild_cooldown`: Minimum duration between invocations, per guild
 - `channel_cooldown`: Minimum duration between invocations, per channel
 - `member_cooldown`: Minimum duration between invocations, per guild member
+- `global_cooldown_uses`, `user_cooldown_uses`, `guild_cooldown_uses`, `channel_cooldown_uses`,
+  `member_cooldown_uses`: Allow this many invocations per cooldown duration instead of just one
+    - For example, `user_cooldown = 60, user_cooldown_uses = 5` allows 5 uses per minute per user
+- `global_cooldown_burst`, `user_cooldown_burst`, `guild_cooldown_burst`, `channel_cooldown_burst`,
+  `member_cooldown_burst`: Number of additional invocations allowed in quick succession
 
 # Function parameters
 
@@ -53,6 +63,8 @@ All following parameters are inputs to the command. You can use all types that i
 You can also wrap types in `Option` or `Vec` to make them optional or variadic. In addition, there
 are multiple attributes you can use on parameters:
 - `#[description = ""]`: Sets description of the parameter (slash-only)
+- `#[name_localized("locale", "name")]`: Localized parameter name for the given locale. Can be repeated for multiple locales (slash-only)
+- `#[description_localized("locale", "description")]`: Localized parameter description for the given locale. Can be repeated for multiple locales (slash-only)
 - `#[autocomplete = ""]`: Sets the autocomplete callback (slash-only)
 - `#[channel_types("", "")]`: For channel parameters, restricts allowed channel types (slash-only)
 - `#[min = 0]`: Minimum value for this number parameter (slash-only)
@@ -124,6 +136,7 @@ is mainly useful in slash commands. It allows you to constrain input to a fixed
 #[derive(Debug, poise::SlashChoiceParameter)]
 pub enum MyStringChoice {
     #[name = "The first choice"]
+    #[name_localized("ja", "最初の選択肢")]
     ChoiceA,
     #[name = "The second choice"]
     ChoiceB,
@@ -139,8 +152,11 @@ pub async fn choice(
     Ok(())
 }
 ```
+
+The name displayed in Discord can be translated per locale with
+`#[name_localized("locale", "name")]`, which can be repeated for multiple locales.
 */
-#[proc_macro_derive(SlashChoiceParameter, attributes(name))]
+#[proc_macro_derive(SlashChoiceParameter, attributes(name, name_localized))]
 pub fn slash_choice_parameter(input: TokenStream) -> TokenStream {
     let enum_ = syn::parse_macro_input!(input as syn::DeriveInput);
 
@@ -149,3 +165,51 @@ pub fn slash_choice_parameter(input: TokenStream) -> TokenStream {
         Err(e) => e.write_errors().into(),
     }
 }
+
+/**
+Use this derive macro on a struct to turn it into a Discord modal, i.e. a form with text inputs. Each
+field becomes a text input. Fields must be `String` (required input) or `Option<String>` (optional
+input). At most five fields are allowed.
+
+Open the modal with `poise::ApplicationContext::execute_modal`.
+
+# Example
+
+```rust
+#[derive(Debug, poise::Modal)]
+#[name = "Modal title"]
+struct MyModal {
+    #[name = "First input label"]
+    #[placeholder = "Your first input goes here"]
+    #[min_length = 5]
+    #[max_length = 100]
+    first_input: String,
+    #[name = "Second input label"]
+    #[paragraph]
+    second_input: Option<String>,
+}
+```
+
+# Struct attributes
+
+- `#[name = "..."]`: title of the modal (defaults to the struct name)
+
+# Field attributes
+
+- `#[name = "..."]`: label of the text input (defaults to the field name)
+- `#[placeholder = "..."]`: text shown while the input is empty
+- `#[min_length = N]`, `#[max_length = N]`: limits on the length of the input
+- `#[paragraph]`: use a multi-line text input
+*/
+#[proc_macro_derive(
+    Modal,
+    attributes(name, placeholder, min_length, max_length, paragraph)
+)]
+pub fn modal(input: TokenStream) -> TokenStream {
+    let struct_ = syn::parse_macro_input!(input as syn::DeriveInput);
+
+    match modal::modal(struct_) {
+        Ok(x) => x,
+        Err(e) => e.write_errors().into(),
+    }
+}
@@ -0,0 +1,155 @@
+//! Implements the #[derive(Modal)] derive macro
+
+use proc_macro::TokenStream;
+use syn::spanned::Spanned as _;
+
+/// Representation of the struct attributes
+#[derive(Debug, Default, darling::FromMeta)]
+#[darling(default)]
+struct StructAttributes {
+    name: Option<String>,
+}
+
+/// Representation of the struct field attributes
+#[derive(Debug, Default, darling::FromMeta)]
+#[darling(default)]
+struct FieldAttributes {
+    name: Option<String>,
+    placeholder: Option<String>,
+    min_length: Option<u64>,
+    max_length: Option<u64>,
+    paragraph: bool,
+}
+
+/// Parses a list of attributes like `#[name = "..."] #[paragraph]` into the given darling type
+fn parse_attributes<T: darling::FromMeta>(attrs: Vec<syn::Attribute>) -> Result<T, darling::Error> {
+    let attrs = attrs
+        .into_iter()
+        .map(|attr| attr.parse_meta().map(syn::NestedMeta::Meta))
+        .collect::<Result<Vec<_>, _>>()?;
+    T::from_list(&attrs)
+}
+
+/// Returns whether the given type is `String`
+fn is_string_type(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(x) => x.path.segments.last().map_or(false, |s| {
+            s.ident == "String" && matches!(s.arguments, syn::PathArguments::None)
+        }),
+        _ => false,
+    }
+}
+
+/// If the given type is `Option<T>`, returns `T`
+fn extract_option_type(ty: &syn::Type) -> Option<&syn::Type> {
+    let path = match ty {
+        syn::Type::Path(x) => &x.path,
+        _ => return None,
+    };
+    let segment = path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => match args.args.first()? {
+            syn::GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+pub fn modal(input: syn::DeriveInput) -> Result<TokenStream, darling::Error> {
+    let struct_ident = input.ident;
+    let fields = match input.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Named(fields),
+            ..
+        }) => fields.named,
+        _ => {
+            return Err(syn::Error::new(
+                struct_ident.span(),
+                "Only structs with named fields can be used as modals",
+            )
+            .into())
+        }
+    };
+    if fields.len() > 5 {
+        return Err(syn::Error::new(fields.span(), "Modals can have at most five inputs").into());
+    }
+
+    let struct_attrs = parse_attributes::<StructAttributes>(input.attrs)?;
+    let title = struct_attrs
+        .name
+        .unwrap_or_else(|| struct_ident.to_string());
+
+    let mut builders = Vec::new();
+    let mut parsers = Vec::new();
+    for field in fields {
+        let ident = field.ident.expect("named fields have idents");
+        let custom_id = ident.to_string();
+        let inner_type = extract_option_type(&field.ty);
+        if !is_string_type(inner_type.unwrap_or(&field.ty)) {
+            return Err(syn::Error::new(
+                field.ty.span(),
+                "Modal fields must be of type `String` or `Option<String>`",
+            )
+            .into());
+        }
+        let required = inner_type.is_none();
+        let attrs = parse_attributes::<FieldAttributes>(field.attrs)?;
+
+        let label = attrs.name.unwrap_or_else(|| custom_id.clone());
+        let style = if attrs.paragraph {
+            quote::quote! { poise::serenity_prelude::InputTextStyle::Paragraph }
+        } else {
+            quote::quote! { poise::serenity_prelude::InputTextStyle::Short }
+        };
+        let placeholder = attrs.placeholder.iter();
+        let min_length = attrs.min_length.iter();
+        let max_length = attrs.max_length.iter();
+        builders.push(quote::quote! {
+            .create_action_row(|r| r.create_input_text(|t| {
+                t.custom_id(#custom_id)
+                    .label(#label)
+                    .style(#style)
+                    .required(#required);
+                #( t.placeholder(#placeholder); )*
+                #( t.min_length(#min_length); )*
+                #( t.max_length(#max_length); )*
+                t
+            }))
+        });
+
+        let value = quote::quote! { poise::_find_modal_text(&mut data, #custom_id) };
+        parsers.push(if required {
+            quote::quote! { #ident: #value.ok_or(concat!("missing modal field: ", #custom_id))? }
+        } else {
+            quote::quote! { #ident: #value }
+        });
+    }
+
+    Ok(quote::quote! {
+        impl poise::Modal for #struct_ident {
+            fn create(
+                builder: &mut poise::serenity_prelude::CreateInteractionResponse,
+                custom_id: String,
+            ) {
+                builder
+                    .kind(poise::serenity_prelude::InteractionResponseType::Modal)
+                    .interaction_response_data(|d| {
+                        d.custom_id(custom_id)
+                            .title(#title)
+                            .components(|c| c #( #builders )* )
+                    });
+            }
+
+            fn parse(
+                mut data: poise::serenity_prelude::ModalSubmitInteractionData,
+            ) -> Result<Self, &'static str> {
+                Ok(Self { #( #parsers, )* })
+            }
+        }
+    }
+    .into())
+}
@@ -8,6 +8,8 @@ use syn::spanned::Spanned as _;
 struct VariantAttribute {
     #[darling(multiple)]
     name: Vec<String>,
+    #[darling(multiple)]
+    name_localized: Vec<crate::command::Tuple2<String>>,
 }
 
 pub fn slash_choice_parameter(input: syn::DeriveInput) -> Result<TokenStream, darling::Error> {
@@ -25,6 +27,8 @@ pub fn slash_choice_parameter(input: syn::DeriveInput) -> Result<TokenStream, da
     let mut variant_idents: Vec<proc_macro2::Ident> = Vec::new();
     let mut display_strings: Vec<String> = Vec::new();
     let mut more_display_strings = Vec::new();
+    let mut locales = Vec::new();
+    let mut localized_names = Vec::new();
 
     for variant in enum_.variants {
         if !matches!(&variant.fields, syn::Fields::Unit) {
@@ -40,7 +44,8 @@ pub fn slash_choice_parameter(input: syn::DeriveInput) -> Result<TokenStream, da
             .into_iter()
             .map(|attr| attr.parse_meta().map(syn::NestedMeta::Meta))
             .collect::<Result<Vec<_>, _>>()?;
-        let mut names = <VariantAttribute as darling::FromMeta>::from_list(&attrs)?.name;
+        let attrs = <VariantAttribute as darling::FromMeta>::from_list(&attrs)?;
+        let mut names = attrs.name;
 
         let main_name = if names.is_empty() {
             variant.ident.to_string()
@@ -51,6 +56,20 @@ pub fn slash_choice_parameter(input: syn::DeriveInput) -> Result<TokenStream, da
         variant_idents.push(variant.ident);
         display_strings.push(main_name);
         more_display_strings.push(names);
+        locales.push(
+            attrs
+                .name_localized
+                .iter()
+                .map(|crate::command::Tuple2(locale, _)| locale.clone())
+                .collect::<Vec<_>>(),
+        );
+        localized_names.push(
+            attrs
+                .name_localized
+                .into_iter()
+                .map(|crate::command::Tuple2(_, name)| name)
+                .collect::<Vec<_>>(),
+        );
     }
 
     let enum_ident = &input.ident;
@@ -82,6 +101,10 @@ pub fn slash_choice_parameter(input: syn::DeriveInput) -> Result<TokenStream, da
                 builder
                     .kind(poise::serenity_prelude::ApplicationCommandOptionType::Integer)
                     #( .add_int_choice(#display_strings, #indices2 as i32) )* ;
+                poise::_set_choice_name_localizations(
+                    builder,
+                    &[ #( &[ #( (#locales, #localized_names) ),* ] ),* ],
+                );
             }
         }
 
@@ -11,6 +11,9 @@ pub struct HelpConfiguration<'a> {
     pub ephemeral: bool,
     /// Whether to list context menu commands as well
     pub show_context_menu_commands: bool,
+    /// How long the page navigation of [`paginated_help()`] stays active after the last
+    /// interaction
+    pub pagination_timeout: std::time::Duration,
 }
 
 impl Default for HelpConfiguration<'_> {
@@ -19,37 +22,210 @@ impl Default for HelpConfiguration<'_> {
             extra_text_at_bottom: "",
             ephemeral: true,
             show_context_menu_commands: false,
+            pagination_timeout: std::time::Duration::from_secs(120),
         }
     }
 }
 
-/// Code for printing help of a specific command (e.g. `~help my_command`)
+/// Returns the prefix that should be displayed in front of the command name in help menus, or None
+/// if this is neither a prefix nor a slash command (i.e. probably a context menu only command)
+async fn displayed_prefix<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    command: &crate::Command<U, E>,
+) -> Option<String> {
+    if command.slash_action.is_some() {
+        Some(String::from("/"))
+    } else if command.prefix_action.is_some() {
+        let options = &ctx.framework().options().prefix_options;
+
+        Some(match &options.prefix {
+            Some(fixed_prefix) => fixed_prefix.clone(),
+            None => match options.dynamic_prefix {
+                Some(dynamic_prefix_callback) => {
+                    match dynamic_prefix_callback(crate::PartialContext::from(ctx)).await {
+                        Some(dynamic_prefix) => dynamic_prefix,
+                        None => String::from(""),
+                    }
+                }
+                None => String::from(""),
+            },
+        })
+    } else {
+        None
+    }
+}
+
+/// Looks up a command by its qualified name, e.g. `config set`. Each space-separated word is
+/// matched against the names and aliases of the commands on that level. Context menu commands can
+/// be looked up by their context menu name as well
+fn find_command<'a, U, E>(
+    commands: &'a [crate::Command<U, E>],
+    qualified_name: &str,
+) -> Option<&'a crate::Command<U, E>> {
+    let context_menu_command = commands
+        .iter()
+        .find(|command| match command.context_menu_name {
+            Some(context_menu_name) => {
+                context_menu_name.eq_ignore_ascii_case(qualified_name.trim())
+            }
+            None => false,
+        });
+    if let Some(command) = context_menu_command {
+        return Some(command);
+    }
+
+    let mut words = qualified_name.split_whitespace();
+    let mut command = find_by_name(commands, words.next()?)?;
+    for word in words {
+        command = find_by_name(&command.subcommands, word)?;
+    }
+    Some(command)
+}
+
+/// Finds the command with the given name or alias
+fn find_by_name<'a, U, E>(
+    commands: &'a [crate::Command<U, E>],
+    name: &str,
+) -> Option<&'a crate::Command<U, E>> {
+    commands.iter().find(|command| {
+        command.name.eq_ignore_ascii_case(name)
+            || command
+                .aliases
+                .iter()
+                .chain(command.name_localizations.values())
+                .any(|alias| alias.eq_ignore_ascii_case(name))
+    })
+}
+
+/// Formats a duration for display in help menus, e.g. `90s` or `1.5s`
+fn format_duration(duration: std::time::Duration) -> String {
+    if duration.subsec_millis() == 0 {
+        format!("{}s", duration.as_secs())
+    } else {
+        format!("{}s", duration.as_secs_f32())
+    }
+}
+
+/// Describes each configured cooldown bucket of a command in a human readable way, e.g.
+/// `3 uses per 60s per user`
+fn describe_cooldowns(config: &crate::CooldownConfig) -> Vec<String> {
+    let buckets = [
+        (
+            "globally",
+            config.global,
+            config.global_uses,
+            config.global_burst,
+        ),
+        ("per user", config.user, config.user_uses, config.user_burst),
+        (
+            "per guild",
+            config.guild,
+            config.guild_uses,
+            config.guild_burst,
+        ),
+        (
+            "per channel",
+            config.channel,
+            config.channel_uses,
+            config.channel_burst,
+        ),
+        (
+            "per member",
+            config.member,
+            config.member_uses,
+            config.member_burst,
+        ),
+    ];
+
+    let mut descriptions = Vec::new();
+    for &(scope, duration, uses, burst) in &buckets {
+        let duration = match duration {
+            Some(x) => x,
+            None => continue,
+        };
+        let mut description = match uses.unwrap_or(1) {
+            1 => format!("once per {} {}", format_duration(duration), scope),
+            uses => format!("{} uses per {} {}", uses, format_duration(duration), scope),
+        };
+        if burst > 0 {
+            description += &format!(" (+{} burst)", burst);
+        }
+        descriptions.push(description);
+    }
+    descriptions
+}
+
+/// Returns the name to display for the given command in help menus. Slash commands are displayed
+/// with their localized name if available, since that's what users type in their Discord client
+fn displayed_name<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    command: &crate::Command<U, E>,
+    prefix: &str,
+) -> &'static str {
+    if prefix == "/" {
+        command.localized_name(ctx.locale())
+    } else {
+        command.name
+    }
+}
+
+/// Code for printing help of a specific command (e.g. `~help my_command`). Subcommands are looked
+/// up by their qualified name (e.g. `~help config set`)
 async fn help_single_command<U, E>(
     ctx: crate::Context<'_, U, E>,
     command_name: &str,
     config: HelpConfiguration<'_>,
 ) -> Result<(), serenity::Error> {
-    let command = ctx.framework().options().commands.iter().find(|command| {
-        if command.name.eq_ignore_ascii_case(command_name) {
-            return true;
-        }
-        if let Some(context_menu_name) = command.context_menu_name {
-            if context_menu_name.eq_ignore_ascii_case(command_name) {
-                return true;
-            }
-        }
-
-        false
-    });
+    let command = find_command(&ctx.framework().options().commands, command_name);
 
     let reply = if let Some(command) = command {
-        match command.multiline_help {
+        let mut reply = match command.multiline_help {
             Some(f) => f(),
             None => command
-                .inline_help
+                .localized_inline_help(ctx.locale())
                 .unwrap_or("No help available")
                 .to_owned(),
+        };
+
+        if let Some(prefix) = displayed_prefix(ctx, command).await {
+            reply += &format!("\n\nUsage: `{}`", command.usage(&prefix));
         }
+
+        let subcommands = command
+            .subcommands
+            .iter()
+            .filter(|subcommand| !subcommand.hide_in_help)
+            .collect::<Vec<_>>();
+        if !subcommands.is_empty() {
+            reply += "\n\nSubcommands:";
+            for subcommand in subcommands {
+                reply += &format!("\n`{}`", subcommand.qualified_name);
+                if let Some(inline_help) = subcommand.localized_inline_help(ctx.locale()) {
+                    reply += &format!(" - {}", inline_help);
+                }
+            }
+        }
+
+        let mut details = String::new();
+        if !command.aliases.is_empty() {
+            details += &format!("\nAliases: {}", command.aliases.join(", "));
+        }
+        let cooldowns = describe_cooldowns(command.cooldowns.config());
+        if !cooldowns.is_empty() {
+            details += &format!("\nCooldowns: {}", cooldowns.join(", "));
+        }
+        if !command.required_permissions.is_empty() {
+            details += &format!("\nRequired permissions: {}", command.required_permissions);
+        }
+        if command.owners_only {
+            details += "\nOnly usable by bot owners";
+        }
+        if !details.is_empty() {
+            reply += "\n";
+            reply += &details;
+        }
+
+        reply
     } else {
         format!("No such command `{}`", command_name)
     };
@@ -80,37 +256,22 @@ async fn help_all_commands<U, E>(
                 continue;
             }
 
-            let prefix = if command.slash_action.is_some() {
-                String::from("/")
-            } else if command.prefix_action.is_some() {
-                let options = &ctx.framework().options().prefix_options;
-
-                match &options.prefix {
-                    Some(fixed_prefix) => fixed_prefix.clone(),
-                    None => match options.dynamic_prefix {
-                        Some(dynamic_prefix_callback) => {
-                            match dynamic_prefix_callback(crate::PartialContext::from(ctx)).await {
-                                Some(dynamic_prefix) => dynamic_prefix,
-                                None => String::from(""),
-                            }
-                        }
-                        None => String::from(""),
-                    },
-                }
-            } else {
+            let prefix = match displayed_prefix(ctx, command).await {
+                Some(prefix) => prefix,
                 // This is not a prefix or slash command, i.e. probably a context menu only command
                 // which we will only show later
-                continue;
+                None => continue,
             };
 
-            let total_command_name_length = prefix.chars().count() + command.name.chars().count();
+            let name = displayed_name(ctx, command, &prefix);
+            let total_command_name_length = prefix.chars().count() + name.chars().count();
             let padding = 12_usize.saturating_sub(total_command_name_length) + 1;
             menu += &format!(
                 "  {}{}{}{}\n",
                 prefix,
-                command.name,
+                name,
                 " ".repeat(padding),
-                command.inline_help.unwrap_or("")
+                command.localized_inline_help(ctx.locale()).unwrap_or("")
             );
         }
     }
@@ -201,3 +362,408 @@ pub async fn help<U, E>(
         None => help_all_commands(ctx, config).await,
     }
 }
+
+/// A single page of [`paginated_help()`]
+struct HelpPage {
+    /// Embed title, i.e. the category name
+    title: String,
+    /// One line per command
+    lines: Vec<String>,
+}
+
+/// Maximum number of commands on a single page of [`paginated_help()`]. Larger categories are split
+/// into multiple pages
+const COMMANDS_PER_PAGE: usize = 15;
+
+/// Groups the commands into pages by category, for [`paginated_help()`]
+async fn help_pages<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    config: &HelpConfiguration<'_>,
+) -> Vec<HelpPage> {
+    let mut categories = crate::util::OrderedMap::<Option<&str>, Vec<String>>::new();
+    for command in &ctx.framework().options().commands {
+        if command.hide_in_help {
+            continue;
+        }
+        let prefix = match displayed_prefix(ctx, command).await {
+            Some(prefix) => prefix,
+            None => continue,
+        };
+
+        let name = displayed_name(ctx, command, &prefix);
+        let line = match command.localized_inline_help(ctx.locale()) {
+            Some(inline_help) => format!("`{}{}` - {}", prefix, name, inline_help),
+            None => format!("`{}{}`", prefix, name),
+        };
+        categories
+            .get_or_insert_with(command.category, Vec::new)
+            .push(line);
+    }
+
+    if config.show_context_menu_commands {
+        let mut lines = Vec::new();
+        for command in &ctx.framework().options().commands {
+            let kind = match command.context_menu_action {
+                Some(crate::ContextMenuCommandAction::User(_)) => "user",
+                Some(crate::ContextMenuCommandAction::Message(_)) => "message",
+                None => continue,
+            };
+            let name = command.context_menu_name.unwrap_or(command.name);
+            lines.push(format!("`{}` (on {})", name, kind));
+        }
+        if !lines.is_empty() {
+            categories
+                .get_or_insert_with(Some("Context menu commands"), Vec::new)
+                .extend(lines);
+        }
+    }
+
+    let mut pages = Vec::new();
+    for (category_name, lines) in categories {
+        let category_name = category_name.unwrap_or("Commands");
+        let num_chunks = lines.chunks(COMMANDS_PER_PAGE).len();
+        for (i, chunk) in lines.chunks(COMMANDS_PER_PAGE).enumerate() {
+            let title = if num_chunks > 1 {
+                format!("{} ({}/{})", category_name, i + 1, num_chunks)
+            } else {
+                category_name.to_owned()
+            };
+            pages.push(HelpPage {
+                title,
+                lines: chunk.to_vec(),
+            });
+        }
+    }
+    pages
+}
+
+/// Renders a page of [`paginated_help()`] into an embed
+fn help_page_embed(
+    pages: &[HelpPage],
+    current_page: usize,
+    config: &HelpConfiguration<'_>,
+) -> serenity::CreateEmbed {
+    let page = &pages[current_page];
+
+    let mut description = page.lines.join("\n");
+    if !config.extra_text_at_bottom.is_empty() {
+        description += "\n\n";
+        description += config.extra_text_at_bottom;
+    }
+
+    let mut embed = serenity::CreateEmbed::default();
+    embed
+        .title(&page.title)
+        .description(description)
+        .footer(|f| f.text(format!("Page {}/{}", current_page + 1, pages.len())));
+    embed
+}
+
+/// Discord allows at most this many options per select menu
+const MAX_SELECT_OPTIONS: usize = 25;
+
+/// Returns the pages which are listed in the select menu of [`paginated_help()`]. If there are
+/// too many pages for a single select menu, it's a window around the current page
+fn select_menu_pages(num_pages: usize, current_page: usize) -> std::ops::Range<usize> {
+    let start = current_page
+        .saturating_sub(MAX_SELECT_OPTIONS / 2)
+        .min(num_pages.saturating_sub(MAX_SELECT_OPTIONS));
+    start..num_pages.min(start + MAX_SELECT_OPTIONS)
+}
+
+/// Creates the navigation components of [`paginated_help()`]: previous and next buttons, and a
+/// select menu to jump to a category
+fn help_page_components(
+    id: u64,
+    pages: &[HelpPage],
+    current_page: usize,
+    disabled: bool,
+) -> serenity::CreateComponents {
+    let mut components = serenity::CreateComponents::default();
+    components.create_action_row(|row| {
+        row.create_button(|b| {
+            b.custom_id(format!("{}:prev", id))
+                .label("Previous")
+                .style(serenity::ButtonStyle::Secondary)
+                .disabled(disabled || current_page == 0)
+        })
+        .create_button(|b| {
+            b.custom_id(format!("{}:next", id))
+                .label("Next")
+                .style(serenity::ButtonStyle::Secondary)
+                .disabled(disabled || current_page + 1 == pages.len())
+        })
+    });
+    components.create_action_row(|row| {
+        row.create_select_menu(|menu| {
+            menu.custom_id(format!("{}:select", id))
+                .placeholder("Jump to category")
+                .disabled(disabled)
+                .options(|options| {
+                    for i in select_menu_pages(pages.len(), current_page) {
+                        let page = &pages[i];
+                        options.create_option(|o| {
+                            o.label(&page.title)
+                                .value(i)
+                                .default_selection(i == current_page)
+                        });
+                    }
+                    options
+                })
+        })
+    });
+    components
+}
+
+/// A help command like [`help()`], but the overview of all commands is displayed as embeds with one
+/// page per category. Users can navigate between the pages with buttons and a select menu.
+///
+/// Navigation only reacts to the user who invoked the help command and stops after
+/// [`HelpConfiguration::pagination_timeout`] without interaction, at which point the components are
+/// disabled. Help for a specific command is displayed like in [`help()`].
+///
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # type Context<'a> = poise::Context<'a, (), Error>;
+/// /// Show this menu
+/// #[poise::command(prefix_command, slash_command)]
+/// pub async fn help(
+///     ctx: Context<'_>,
+///     #[description = "Specific command to show help about"] command: Option<String>,
+/// ) -> Result<(), Error> {
+///     let config = poise::builtins::HelpConfiguration {
+///         extra_text_at_bottom: "Type ?help command for more info on a command.",
+///         ..Default::default()
+///     };
+///     poise::builtins::paginated_help(ctx, command.as_deref(), config).await?;
+///     Ok(())
+/// }
+/// ```
+pub async fn paginated_help<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    command: Option<&str>,
+    config: HelpConfiguration<'_>,
+) -> Result<(), serenity::Error> {
+    if let Some(command) = command {
+        return help_single_command(ctx, command, config).await;
+    }
+
+    let pages = help_pages(ctx, &config).await;
+    if pages.is_empty() {
+        ctx.send(|f| {
+            f.content("No commands available")
+                .ephemeral(config.ephemeral)
+        })
+        .await?;
+        return Ok(());
+    }
+
+    let id = ctx.id();
+    let mut current_page = 0;
+    let reply = ctx
+        .send(|f| {
+            f.embeds
+                .push(help_page_embed(&pages, current_page, &config));
+            f.components = Some(help_page_components(id, &pages, current_page, false));
+            f.ephemeral(config.ephemeral)
+        })
+        .await?;
+
+    let id_prefix = format!("{}:", id);
+    let author_id = ctx.author().id;
+    loop {
+        let filter_prefix = id_prefix.clone();
+        let interaction = ctx
+            .framework()
+            .wait_for_interaction(
+                move |i| match i {
+                    serenity::Interaction::MessageComponent(i) => {
+                        let action = i.data.custom_id.strip_prefix(filter_prefix.as_str());
+                        i.user.id == author_id
+                            && matches!(action, Some("prev") | Some("next") | Some("select"))
+                    }
+                    _ => false,
+                },
+                config.pagination_timeout,
+            )
+            .await;
+        let interaction = match interaction {
+            Some(serenity::Interaction::MessageComponent(x)) => x,
+            _ => break,
+        };
+
+        let action = &interaction.data.custom_id[id_prefix.len()..];
+        current_page = match action {
+            "prev" => current_page.saturating_sub(1),
+            "next" => (current_page + 1).min(pages.len() - 1),
+            "select" => interaction
+                .data
+                .values
+                .first()
+                .and_then(|value| value.parse().ok())
+                .filter(|&page| page < pages.len())
+                .unwrap_or(current_page),
+            _ => current_page,
+        };
+
+        let page = crate::CreateReply {
+            embeds: vec![help_page_embed(&pages, current_page, &config)],
+            components: Some(help_page_components(id, &pages, current_page, false)),
+            ..Default::default()
+        };
+        let request = crate::ResponseRequest::InitialResponse {
+            interaction: crate::InteractionRef::Component(&interaction),
+            response: crate::InitialResponse::UpdateMessage(page),
+        };
+        ctx.framework()
+            .send_response(ctx.discord(), request)
+            .await?;
+    }
+
+    // Navigation timed out; disable the components so that users don't click dead buttons
+    if let Some(reply) = reply {
+        reply
+            .edit(ctx, |f| {
+                f.embeds
+                    .push(help_page_embed(&pages, current_page, &config));
+                f.components = Some(help_page_components(id, &pages, current_page, true));
+                f
+            })
+            .await?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_describe_cooldowns() {
+    use std::time::Duration;
+
+    assert!(describe_cooldowns(&crate::CooldownConfig::default()).is_empty());
+
+    let config = crate::CooldownConfig {
+        user: Some(Duration::from_secs(5)),
+        guild: Some(Duration::from_secs(60)),
+        guild_uses: Some(3),
+        guild_burst: 2,
+        ..Default::default()
+    };
+    assert_eq!(
+        describe_cooldowns(&config),
+        vec![
+            "once per 5s per user",
+            "3 uses per 60s per guild (+2 burst)"
+        ],
+    );
+    assert_eq!(format_duration(Duration::from_millis(1500)), "1.5s");
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_paginated_help() {
+    let command = |name, category| crate::Command::<(), String> {
+        name,
+        qualified_name: name.into(),
+        category,
+        prefix_action: Some(|ctx, _| {
+            Box::pin(async move {
+                let config = HelpConfiguration {
+                    pagination_timeout: std::time::Duration::from_millis(50),
+                    ..Default::default()
+                };
+                let _ = paginated_help(ctx.into(), None, config).await;
+                Ok(())
+            })
+        }),
+        ..Default::default()
+    };
+    let harness = crate::testing::TestHarness::new(
+        crate::FrameworkOptions {
+            commands: vec![
+                command("help", Some("Help")),
+                command("other", Some("Other")),
+            ],
+            prefix_options: crate::PrefixFrameworkOptions {
+                prefix: Some("~".into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        (),
+    );
+
+    let msg = harness.message("~help");
+    let event = crate::Event::Message {
+        new_message: msg.clone(),
+    };
+    let run_command = harness.dispatch(&event);
+    let custom_id = format!("{}:next", msg.id);
+    let click = harness.click_component(&msg, &custom_id);
+    tokio::join!(run_command, click);
+
+    let kinds = harness
+        .take_replies()
+        .into_iter()
+        .map(|reply| reply.kind)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        vec![
+            crate::testing::CapturedReplyKind::Message,
+            crate::testing::CapturedReplyKind::ComponentMessageUpdate,
+            // Components are disabled after the timeout
+            crate::testing::CapturedReplyKind::MessageEdit,
+        ]
+    );
+
+    assert_eq!(select_menu_pages(3, 2), 0..3);
+    assert_eq!(select_menu_pages(30, 0), 0..25);
+    assert_eq!(select_menu_pages(30, 15), 3..28);
+    assert_eq!(select_menu_pages(30, 29), 5..30);
+
+    // With more pages than select menu options, the select menu follows the current page
+    let commands = (0..30)
+        .map(|i| {
+            let name: &'static str = Box::leak(format!("cmd{}", i).into_boxed_str());
+            command(name, Some(name))
+        })
+        .collect();
+    let harness = crate::testing::TestHarness::new(
+        crate::FrameworkOptions {
+            commands,
+            prefix_options: crate::PrefixFrameworkOptions {
+                prefix: Some("~".into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        (),
+    );
+
+    let msg = harness.message("~cmd0");
+    let event = crate::Event::Message {
+        new_message: msg.clone(),
+    };
+    let run_command = harness.dispatch(&event);
+    let mut interaction = harness.component_interaction(&msg, &format!("{}:select", msg.id));
+    interaction.data.values = vec!["29".into()];
+    let select_event = crate::Event::InteractionCreate {
+        interaction: serenity::Interaction::MessageComponent(interaction),
+    };
+    let select = harness.dispatch(&select_event);
+    tokio::join!(run_command, select);
+
+    let select_options = |reply: &crate::testing::CapturedReply| {
+        let rows = &reply.components.as_ref().unwrap().0;
+        rows[1]["components"][0]["options"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|option| option["value"].as_str().unwrap().to_owned())
+            .collect::<Vec<_>>()
+    };
+    let replies = harness.take_replies();
+    let expected = |range: std::ops::Range<usize>| range.map(|i| i.to_string()).collect::<Vec<_>>();
+    assert_eq!(select_options(&replies[0]), expected(0..25));
+    assert_eq!(select_options(&replies[1]), expected(5..30));
+}
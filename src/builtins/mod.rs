@@ -6,11 +6,14 @@
 mod help;
 pub use help::*;
 
+mod sync;
+pub use sync::*;
+
 use crate::serenity_prelude as serenity;
 
-/// An error handler that prints the error into the console and also into the Discord chat.
-/// If the user invoked the command wrong ([`crate::FrameworkError::ArgumentParse`]), the command
-/// help is displayed and the user is directed to the help menu.
+/// An error handler that logs the error via [`tracing`] and also prints it into the Discord chat.
+/// If the user invoked the command wrong ([`crate::FrameworkError::ArgumentParse`]), the command's
+/// usage signature and help are displayed and the user is directed to the help menu.
 ///
 /// Can return an error if sending the Discord error message failed. You can decide for yourself
 /// how to handle this, for example:
@@ -25,23 +28,40 @@ pub async fn on_error<U, E: std::fmt::Display + std::fmt::Debug>(
     error: crate::FrameworkError<'_, U, E>,
 ) -> Result<(), serenity::Error> {
     match error {
-        crate::FrameworkError::Setup { error } => println!("Error in user data setup: {}", error),
-        crate::FrameworkError::Listener { error, event } => println!(
-            "User event listener encountered an error on {} event: {}",
-            event.name(),
-            error
+        crate::FrameworkError::Setup { error } => {
+            tracing::error!(error = %error, "error in user data setup")
+        }
+        crate::FrameworkError::CommandRegistration { error, guild_id } => tracing::error!(
+            error = %error,
+            guild_id = guild_id.map(|id| id.0),
+            "failed to register application commands"
+        ),
+        crate::FrameworkError::Listener { error, event } => tracing::error!(
+            error = %error,
+            event = event.name(),
+            "user event listener encountered an error"
         ),
         crate::FrameworkError::Command { ctx, error } => {
+            tracing::error!(
+                error = %error,
+                command = %ctx.command().qualified_name,
+                user_id = ctx.author().id.0,
+                "command returned an error"
+            );
             let error = error.to_string();
             ctx.say(error).await?;
         }
         crate::FrameworkError::ArgumentParse { ctx, input, error } => {
             // If we caught an argument parse error, give a helpful error message with the
-            // command explanation if available
-            let usage = match ctx.command().multiline_help {
-                Some(multiline_help) => multiline_help(),
-                None => "Please check the help menu for usage information".into(),
-            };
+            // usage signature and the command explanation if available
+            let mut usage = format!("Usage: `{}`", ctx.command().usage(ctx.prefix()));
+            match ctx.command().multiline_help {
+                Some(multiline_help) => {
+                    usage += "\n";
+                    usage += &multiline_help();
+                }
+                None => usage += "\nPlease check the help menu for more information",
+            }
             let response = if let Some(input) = input {
                 format!(
                     "**Cannot parse `{}` as argument: {}**\n{}",
@@ -53,25 +73,44 @@ pub async fn on_error<U, E: std::fmt::Display + std::fmt::Debug>(
             ctx.say(response).await?;
         }
         crate::FrameworkError::CommandStructureMismatch { ctx, description } => {
-            println!(
-                "Error: failed to deserialize interaction arguments for `/{}`: {}",
-                ctx.command.name, description,
+            tracing::error!(
+                command = %ctx.command.qualified_name,
+                description,
+                "failed to deserialize interaction arguments"
             );
         }
         crate::FrameworkError::CommandCheckFailed { ctx, error } => {
-            println!(
-                "A command check failed in command {} for user {}: {:?}",
-                ctx.command().name,
-                ctx.author().name,
-                error,
+            tracing::warn!(
+                error = ?error,
+                command = %ctx.command().qualified_name,
+                user_id = ctx.author().id.0,
+                "command check failed"
             );
         }
         crate::FrameworkError::CooldownHit {
             remaining_cooldown,
+            bucket,
+            remaining_uses,
+            capacity,
             ctx,
         } => {
+            let reason = match bucket {
+                crate::CooldownBucket::User(_) | crate::CooldownBucket::Member(_, _) => {
+                    "You're too fast"
+                }
+                crate::CooldownBucket::Global => "This command is being used too often",
+                crate::CooldownBucket::Guild(_) => {
+                    "This command is being used too often in this server"
+                }
+                crate::CooldownBucket::Channel(_) => {
+                    "This command is being used too often in this channel"
+                }
+            };
             let msg = format!(
-                "You're too fast. Please wait {} seconds before retrying",
+                "{} ({} of {} uses left). Please wait {} seconds before retrying",
+                reason,
+                remaining_uses,
+                capacity,
                 remaining_cooldown.as_secs()
             );
             ctx.send(|b| b.content(msg).ephemeral(true)).await?;
@@ -94,22 +133,64 @@ pub async fn on_error<U, E: std::fmt::Display + std::fmt::Debug>(
                 format!(
                     "You're lacking permissions for `{}{}`: {}",
                     ctx.prefix(),
-                    ctx.command().name,
+                    ctx.command().localized_name(ctx.locale()),
                     missing_permissions,
                 )
             } else {
                 format!(
                     "You may be lacking permissions for `{}{}`. Not executing for safety",
                     ctx.prefix(),
-                    ctx.command().name,
+                    ctx.command().localized_name(ctx.locale()),
                 )
             };
             ctx.send(|b| b.content(response).ephemeral(true)).await?;
         }
+        crate::FrameworkError::NotInVoiceChannel { ctx } => {
+            let response = "You need to be in a voice channel to use this command";
+            ctx.send(|b| b.content(response).ephemeral(true)).await?;
+        }
+        crate::FrameworkError::UnknownCommand {
+            msg,
+            prefix,
+            msg_content,
+            suggestions,
+            ctx,
+        } => {
+            // Without suggestions, there's nothing helpful to say, so stay silent like for any
+            // other message that isn't a command
+            if let Some((first, rest)) = suggestions.split_first() {
+                let command_name = msg_content.split_whitespace().next().unwrap_or("");
+                let mut response = format!(
+                    "Unknown command `{}{}`. Did you mean `{}{}`",
+                    prefix, command_name, prefix, first
+                );
+                for suggestion in rest {
+                    response += &format!(" or `{}{}`", prefix, suggestion);
+                }
+                response += "?";
+                msg.channel_id.say(ctx.discord, response).await?;
+            }
+        }
         crate::FrameworkError::NotAnOwner { ctx } => {
             let response = "Only bot owners can call this command";
             ctx.send(|b| b.content(response).ephemeral(true)).await?;
         }
+        crate::FrameworkError::ComponentHandler { error, ctx } => {
+            let error = error.to_string();
+            ctx.send(|b| b.content(error).ephemeral(true)).await?;
+        }
+        crate::FrameworkError::ComponentCheckFailed { error, ctx } => match error {
+            Some(error) => tracing::warn!(
+                error = ?error,
+                custom_id = ctx.custom_id(),
+                user_id = ctx.author().id.0,
+                "component check failed"
+            ),
+            None => {
+                let response = "You're not allowed to use this component";
+                ctx.send(|b| b.content(response).ephemeral(true)).await?;
+            }
+        },
     }
 
     Ok(())
@@ -132,6 +213,9 @@ pub async fn autocomplete_command<U, E>(
 
 /// Generic function to register application commands, either globally or in a guild.
 ///
+/// Only the commands which changed since the last registration are sent to Discord, see
+/// [`sync_application_commands`].
+///
 /// Some permission checks are built in:
 /// - global command registration is only allowed for bot owners
 /// - guild-specific command registration is allowed for guild owners and bot owners
@@ -147,31 +231,16 @@ pub async fn register_application_commands<U, E>(
     ctx: crate::Context<'_, U, E>,
     global: bool,
 ) -> Result<(), serenity::Error> {
-    let mut commands_builder = serenity::CreateApplicationCommands::default();
     let commands = &ctx.framework().options().commands;
-    for command in commands {
-        if let Some(slash_command) = command.create_as_slash_command() {
-            commands_builder.add_application_command(slash_command);
-        }
-        if let Some(context_menu_command) = command.create_as_context_menu_command() {
-            commands_builder.add_application_command(context_menu_command);
-        }
-    }
-    let commands_builder = serenity::json::Value::Array(commands_builder.0);
 
     let is_bot_owner = ctx.framework().options().owners.contains(&ctx.author().id);
-    if global {
+    let guild_id = if global {
         if !is_bot_owner {
             ctx.say("Can only be used by bot owner").await?;
             return Ok(());
         }
 
-        ctx.say(format!("Registering {} commands...", commands.len()))
-            .await?;
-        ctx.discord()
-            .http
-            .create_global_application_commands(&commands_builder)
-            .await?;
+        None
     } else {
         let guild = match ctx.guild() {
             Some(x) => x,
@@ -187,15 +256,14 @@ pub async fn register_application_commands<U, E>(
             return Ok(());
         }
 
-        ctx.say(format!("Registering {} commands...", commands.len()))
-            .await?;
-        ctx.discord()
-            .http
-            .create_guild_application_commands(guild.id.0, &commands_builder)
-            .await?;
-    }
+        Some(guild.id)
+    };
+
+    ctx.say(format!("Registering {} commands...", commands.len()))
+        .await?;
+    let report = sync_application_commands(&ctx.discord().http, guild_id, commands, false).await?;
 
-    ctx.say("Done!").await?;
+    ctx.say(format!("Done!\n{}", report)).await?;
 
     Ok(())
 }
@@ -0,0 +1,480 @@
+//! Differential application command registration, which only sends the changes to Discord instead
+//! of overwriting the entire command list
+
+use crate::serenity_prelude as serenity;
+#[allow(unused_imports)] // import is required if serenity simdjson feature is enabled
+use serenity::json::prelude::*;
+use serenity::json::Value;
+
+/// Outcome of [`sync_application_commands`]: the names of the application commands, sorted into
+/// what happened (or, in a dry run, what would happen) to them
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SyncReport {
+    /// Commands which were not registered yet and have been created
+    pub added: Vec<String>,
+    /// Commands which were registered in an outdated form and have been edited
+    pub updated: Vec<String>,
+    /// Registered commands which don't exist in the framework anymore and have been deleted
+    pub removed: Vec<String>,
+    /// Commands which were already registered in their current form
+    pub unchanged: Vec<String>,
+}
+
+impl SyncReport {
+    /// Returns true if Discord's command list was (or would be) changed
+    pub fn has_changes(&self) -> bool {
+        !(self.added.is_empty() && self.updated.is_empty() && self.removed.is_empty())
+    }
+}
+
+impl std::fmt::Display for SyncReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !self.has_changes() {
+            return write!(f, "No changes ({} unchanged)", self.unchanged.len());
+        }
+
+        for &(label, names) in &[
+            ("Added", &self.added),
+            ("Updated", &self.updated),
+            ("Removed", &self.removed),
+        ] {
+            if !names.is_empty() {
+                writeln!(f, "{}: {}", label, names.join(", "))?;
+            }
+        }
+        write!(f, "{} unchanged", self.unchanged.len())
+    }
+}
+
+/// A single request that's needed to bring the registered commands in line with the framework
+#[derive(Debug, PartialEq)]
+enum SyncAction {
+    /// Register a new command
+    Create(Value),
+    /// Overwrite an existing command
+    Edit(serenity::CommandId, Value),
+    /// Delete an existing command
+    Delete(serenity::CommandId),
+}
+
+/// Returns the value of the given key, treating `null` like a missing key
+fn field<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    value.get(key).filter(|v| !v.is_null())
+}
+
+/// Returns the localizations stored under the given key, treating a missing map like an empty one
+fn localizations(value: &Value, key: &str) -> Value {
+    field(value, key)
+        .cloned()
+        .unwrap_or_else(|| serenity::json::json!({}))
+}
+
+/// Brings a command option into a canonical form, so that options generated by the framework can
+/// be compared with those returned by Discord, which fills in defaults and omits some fields
+fn normalize_option(option: &Value) -> Value {
+    let list = |key| match field(option, key) {
+        Some(Value::Array(items)) => items.clone(),
+        _ => Vec::new(),
+    };
+
+    let choices = list("choices")
+        .iter()
+        .map(|choice| {
+            serenity::json::json!({
+                "name": field(choice, "name"),
+                "name_localizations": localizations(choice, "name_localizations"),
+                "value": field(choice, "value"),
+            })
+        })
+        .collect::<Vec<_>>();
+    let options = list("options")
+        .iter()
+        .map(normalize_option)
+        .collect::<Vec<_>>();
+
+    serenity::json::json!({
+        "type": field(option, "type"),
+        "name": field(option, "name"),
+        "name_localizations": localizations(option, "name_localizations"),
+        "description": field(option, "description").cloned().unwrap_or_else(|| "".into()),
+        "description_localizations": localizations(option, "description_localizations"),
+        "required": field(option, "required").cloned().unwrap_or(Value::Bool(false)),
+        "autocomplete": field(option, "autocomplete").cloned().unwrap_or(Value::Bool(false)),
+        "choices": choices,
+        "options": options,
+        "channel_types": list("channel_types"),
+        "min_value": field(option, "min_value"),
+        "max_value": field(option, "max_value"),
+    })
+}
+
+/// Brings a command into a canonical form. See [`normalize_option`]
+fn normalize_command(command: &Value) -> Value {
+    let options = match field(command, "options") {
+        Some(Value::Array(options)) => options.iter().map(normalize_option).collect(),
+        _ => Vec::new(),
+    };
+
+    serenity::json::json!({
+        "type": field(command, "type").cloned().unwrap_or_else(|| 1.into()),
+        "name": field(command, "name"),
+        "name_localizations": localizations(command, "name_localizations"),
+        "description": field(command, "description").cloned().unwrap_or_else(|| "".into()),
+        "description_localizations": localizations(command, "description_localizations"),
+        "options": options,
+        "default_permission": field(command, "default_permission")
+            .cloned()
+            .unwrap_or(Value::Bool(true)),
+    })
+}
+
+/// Identifies a command. Slash commands and context menu commands may share the same name, so the
+/// command type is included
+fn command_key(command: &Value) -> (Option<&Value>, Option<&str>) {
+    (
+        field(command, "type"),
+        field(command, "name").and_then(|name| name.as_str()),
+    )
+}
+
+/// Compares the registered commands with the desired commands and returns the actions necessary
+/// to get from the former to the latter
+fn plan_sync(
+    registered: &[(serenity::CommandId, Value)],
+    desired: Vec<Value>,
+) -> (Vec<SyncAction>, SyncReport) {
+    let registered = registered
+        .iter()
+        .map(|(id, command)| (*id, normalize_command(command)))
+        .collect::<Vec<_>>();
+
+    let mut actions = Vec::new();
+    let mut report = SyncReport::default();
+    let mut matched = vec![false; registered.len()];
+
+    for command in desired {
+        let normalized = normalize_command(&command);
+        let name = command_key(&normalized).1.unwrap_or_default().to_owned();
+
+        let existing = registered
+            .iter()
+            .position(|(_, existing)| command_key(existing) == command_key(&normalized));
+        match existing {
+            Some(i) => {
+                matched[i] = true;
+                let (id, existing) = &registered[i];
+                if *existing == normalized {
+                    report.unchanged.push(name);
+                } else {
+                    actions.push(SyncAction::Edit(*id, command));
+                    report.updated.push(name);
+                }
+            }
+            None => {
+                actions.push(SyncAction::Create(command));
+                report.added.push(name);
+            }
+        }
+    }
+
+    for ((id, existing), matched) in registered.iter().zip(matched) {
+        if !matched {
+            actions.push(SyncAction::Delete(*id));
+            report
+                .removed
+                .push(command_key(existing).1.unwrap_or_default().to_owned());
+        }
+    }
+
+    (actions, report)
+}
+
+/// Pairs the registered commands, as returned by Discord, with their IDs. Commands without a
+/// valid ID are skipped
+fn registered_commands(commands: Vec<Value>) -> Vec<(serenity::CommandId, Value)> {
+    commands
+        .into_iter()
+        .filter_map(|command| {
+            let id = field(&command, "id")?.as_str()?.parse().ok()?;
+            Some((serenity::CommandId(id), command))
+        })
+        .collect()
+}
+
+/// Fetches the currently registered commands, either globally or in a guild.
+///
+/// The raw JSON is kept instead of going through serenity's command model, which drops
+/// localizations and the autocomplete flag
+async fn fetch_registered_commands(
+    http: &serenity::Http,
+    guild_id: Option<serenity::GuildId>,
+) -> Result<Vec<(serenity::CommandId, Value)>, serenity::Error> {
+    use serenity::http::routing::RouteInfo;
+
+    let application_id = http
+        .application_id()
+        .ok_or(serenity::HttpError::ApplicationIdMissing)?;
+    let route = match guild_id {
+        Some(guild_id) => RouteInfo::GetGuildApplicationCommands {
+            application_id,
+            guild_id: guild_id.0,
+        },
+        None => RouteInfo::GetGlobalApplicationCommands { application_id },
+    };
+    let request = serenity::http::request::RequestBuilder::new(route).build();
+    let commands = http.fire::<Vec<Value>>(request).await?;
+    Ok(registered_commands(commands))
+}
+
+/// Collects the application command payloads of all given commands, in the form that Discord
+/// expects on registration
+pub fn application_command_payloads<U, E>(commands: &[crate::Command<U, E>]) -> Vec<Value> {
+    let mut commands_builder = serenity::CreateApplicationCommands::default();
+    for command in commands {
+        if let Some(slash_command) = command.create_as_slash_command() {
+            commands_builder.add_application_command(slash_command);
+        }
+        if let Some(context_menu_command) = command.create_as_context_menu_command() {
+            commands_builder.add_application_command(context_menu_command);
+        }
+    }
+    commands_builder.0
+}
+
+/// Registers the given commands, either globally (`guild_id` is None) or in a guild, by
+/// comparing them with the currently registered commands and only creating, editing or deleting
+/// those that changed.
+///
+/// With `dry_run` set, no changes are made and the returned report describes what would have
+/// changed.
+///
+/// ```rust,no_run
+/// # async fn _test(ctx: poise::Context<'_, (), ()>) -> Result<(), poise::serenity_prelude::Error> {
+/// let commands = &ctx.framework().options().commands;
+/// let report = poise::builtins::sync_application_commands(
+///     &ctx.discord().http,
+///     ctx.guild_id(),
+///     commands,
+///     true,
+/// )
+/// .await?;
+/// ctx.say(format!("Would apply these changes:\n{}", report)).await?;
+/// # Ok(()) }
+/// ```
+pub async fn sync_application_commands<U, E>(
+    http: &serenity::Http,
+    guild_id: Option<serenity::GuildId>,
+    commands: &[crate::Command<U, E>],
+    dry_run: bool,
+) -> Result<SyncReport, serenity::Error> {
+    sync_application_command_payloads(
+        http,
+        guild_id,
+        application_command_payloads(commands),
+        dry_run,
+    )
+    .await
+}
+
+/// Like [`sync_application_commands`], but takes the payloads from
+/// [`application_command_payloads`], which can be moved into a spawned task unlike the commands
+pub(crate) async fn sync_application_command_payloads(
+    http: &serenity::Http,
+    guild_id: Option<serenity::GuildId>,
+    commands: Vec<Value>,
+    dry_run: bool,
+) -> Result<SyncReport, serenity::Error> {
+    let registered = fetch_registered_commands(http, guild_id).await?;
+    let (actions, report) = plan_sync(&registered, commands);
+    if dry_run {
+        return Ok(report);
+    }
+
+    for action in actions {
+        match (action, guild_id) {
+            (SyncAction::Create(command), Some(guild_id)) => {
+                http.create_guild_application_command(guild_id.0, &command)
+                    .await?;
+            }
+            (SyncAction::Create(command), None) => {
+                http.create_global_application_command(&command).await?;
+            }
+            (SyncAction::Edit(id, command), Some(guild_id)) => {
+                http.edit_guild_application_command(guild_id.0, id.0, &command)
+                    .await?;
+            }
+            (SyncAction::Edit(id, command), None) => {
+                http.edit_global_application_command(id.0, &command).await?;
+            }
+            (SyncAction::Delete(id), Some(guild_id)) => {
+                http.delete_guild_application_command(guild_id.0, id.0)
+                    .await?;
+            }
+            (SyncAction::Delete(id), None) => {
+                http.delete_global_application_command(id.0).await?;
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+#[test]
+fn test_plan_sync() {
+    use serenity::json::json;
+
+    let registered = vec![
+        // Discord fills in defaults and ids
+        (
+            serenity::CommandId(1),
+            json!({ "id": "1", "type": 1, "name": "ping", "description": "Pong",
+                "options": [], "default_permission": true, "version": "1" }),
+        ),
+        (
+            serenity::CommandId(2),
+            json!({ "id": "2", "type": 1, "name": "ban", "description": "Bans a user",
+                "options": [{ "type": 6, "name": "user", "description": "User",
+                    "required": true }] }),
+        ),
+        (
+            serenity::CommandId(3),
+            json!({ "id": "3", "type": 1, "name": "old", "description": "Deprecated" }),
+        ),
+        (
+            serenity::CommandId(4),
+            json!({ "id": "4", "type": 2, "name": "ping", "description": "" }),
+        ),
+    ];
+    let desired = vec![
+        json!({ "name": "ping", "description": "Pong" }),
+        json!({ "name": "ban", "description": "Bans a user",
+            "options": [{ "type": 6, "name": "user", "description": "User", "required": true },
+                { "type": 3, "name": "reason", "description": "Reason" }] }),
+        json!({ "name": "info", "description": "Shows info" }),
+        json!({ "name": "ping", "type": 2 }),
+    ];
+
+    let (actions, report) = plan_sync(&registered, desired.clone());
+    assert_eq!(
+        report,
+        SyncReport {
+            added: vec!["info".into()],
+            updated: vec!["ban".into()],
+            removed: vec!["old".into()],
+            unchanged: vec!["ping".into(), "ping".into()],
+        }
+    );
+    assert_eq!(
+        actions,
+        vec![
+            SyncAction::Edit(serenity::CommandId(2), desired[1].clone()),
+            SyncAction::Create(desired[2].clone()),
+            SyncAction::Delete(serenity::CommandId(3)),
+        ]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_plan_sync_localizations() {
+    use serenity::json::json;
+
+    let registered = vec![(
+        serenity::CommandId(1),
+        json!({ "id": "1", "type": 1, "name": "ping", "description": "Pong",
+            "name_localizations": { "de": "ping" },
+            "options": [{ "type": 3, "name": "text", "description": "Text",
+                "autocomplete": true }] }),
+    )];
+    let command = |name_de, autocomplete| {
+        json!({ "name": "ping", "description": "Pong", "name_localizations": { "de": name_de },
+            "options": [{ "type": 3, "name": "text", "description": "Text",
+                "autocomplete": autocomplete }] })
+    };
+
+    let (actions, report) = plan_sync(&registered, vec![command("ping", true)]);
+    assert!(actions.is_empty());
+    assert_eq!(report.unchanged, vec!["ping".to_owned()]);
+
+    // Only a localization changed
+    let (actions, report) = plan_sync(&registered, vec![command("pingen", true)]);
+    assert_eq!(
+        actions,
+        vec![SyncAction::Edit(
+            serenity::CommandId(1),
+            command("pingen", true)
+        )]
+    );
+    assert_eq!(report.updated, vec!["ping".to_owned()]);
+
+    // Only the autocomplete flag changed
+    let (_, report) = plan_sync(&registered, vec![command("ping", false)]);
+    assert_eq!(report.updated, vec!["ping".to_owned()]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_plan_sync_choice_localizations() {
+    use serenity::json::json;
+
+    let command = |name_de| {
+        json!({ "id": "1", "type": 1, "name": "pick", "description": "Picks",
+            "options": [{ "type": 3, "name": "color", "description": "Color",
+                "choices": [{ "name": "Red", "value": "red",
+                    "name_localizations": { "de": name_de } }] }] })
+    };
+    let registered = vec![(serenity::CommandId(1), command("Rot"))];
+
+    let (_, report) = plan_sync(&registered, vec![command("Rot")]);
+    assert_eq!(report.unchanged, vec!["pick".to_owned()]);
+    let (_, report) = plan_sync(&registered, vec![command("Rote")]);
+    assert_eq!(report.updated, vec!["pick".to_owned()]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_sync_registered_response() {
+    use serenity::json::json;
+
+    let command = crate::Command::<(), ()> {
+        name: "ping",
+        name_localizations: vec![("de", "klingeln")].into_iter().collect(),
+        inline_help: Some("Pong"),
+        description_localizations: vec![("de", "Pong!")].into_iter().collect(),
+        slash_action: Some(|_, _| Box::pin(async { Ok(()) })),
+        parameters: vec![crate::CommandParameter {
+            name: "text",
+            name_localizations: Default::default(),
+            description: Some("Text"),
+            description_localizations: Default::default(),
+            required: true,
+            variadic: false,
+            rest: false,
+            flag: false,
+            channel_types: None,
+            type_setter: Some(|b| {
+                b.kind(serenity::ApplicationCommandOptionType::String);
+            }),
+            autocomplete_callback: Some(|_, _| {
+                Box::pin(async { Ok(serenity::CreateAutocompleteResponse::default()) })
+            }),
+        }],
+        ..Default::default()
+    };
+
+    // What Discord returns for the command registered above
+    let response = vec![json!({
+        "id": "1", "application_id": "2", "version": "3", "type": 1, "name": "ping",
+        "name_localizations": { "de": "klingeln" }, "description": "Pong",
+        "description_localizations": { "de": "Pong!" }, "default_permission": true,
+        "options": [{ "type": 3, "name": "text", "description": "Text", "required": true,
+            "autocomplete": true }],
+    })];
+
+    let registered = registered_commands(response);
+    assert_eq!(registered[0].0, serenity::CommandId(1));
+    let (actions, report) = plan_sync(&registered, application_command_payloads(&[command]));
+    assert!(actions.is_empty());
+    assert_eq!(report.unchanged, vec!["ping".to_owned()]);
+}
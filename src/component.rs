@@ -0,0 +1,325 @@
+//! Persistent handlers for message component interactions (buttons and select menus)
+//!
+//! Unlike collectors, which only live as long as the command that spawned them, handlers are
+//! registered once in [`crate::FrameworkOptions::component_handlers`] and are matched by the
+//! prefix of the component's `custom_id`. Since nothing is held in memory between the message
+//! being sent and the component being clicked, the components keep working across bot restarts.
+//!
+//! ```rust
+//! # type Error = Box<dyn std::error::Error + Send + Sync>;
+//! # use poise::serenity_prelude as serenity;
+//! /// Sends a message with a button that can still be clicked after the bot restarted
+//! #[poise::command(slash_command)]
+//! async fn vote(ctx: poise::Context<'_, (), Error>) -> Result<(), Error> {
+//!     ctx.send(|m| {
+//!         m.content("Cast your vote!").components(|c| {
+//!             c.create_action_row(|r| {
+//!                 r.create_button(|b| b.custom_id("vote:yes").label("Yes"))
+//!                     .create_button(|b| b.custom_id("vote:no").label("No"))
+//!             })
+//!         })
+//!     })
+//!     .await?;
+//!     Ok(())
+//! }
+//!
+//! let options = poise::FrameworkOptions::<(), Error> {
+//!     commands: vec![vote()],
+//!     component_handlers: vec![poise::ComponentHandler {
+//!         custom_id_prefix: "vote:",
+//!         action: |ctx| Box::pin(async move {
+//!             let response = format!("You voted {}", ctx.custom_id_suffix());
+//!             ctx.send(|m| m.content(response).ephemeral(true)).await?;
+//!             Ok(())
+//!         }),
+//!         check: None,
+//!     }],
+//!     ..Default::default()
+//! };
+//! ```
+
+use crate::{serenity_prelude as serenity, BoxFuture};
+
+/// A handler for message component interactions whose `custom_id` starts with a given prefix.
+///
+/// If multiple handlers match, the one with the longest prefix is chosen.
+pub struct ComponentHandler<U, E> {
+    /// Component interactions whose `custom_id` starts with this string are routed to this
+    /// handler. Use the remainder of the `custom_id` (see [`ComponentContext::custom_id_suffix`])
+    /// to encode state, like a page number or the ID of the entity being acted on
+    pub custom_id_prefix: &'static str,
+    /// Callback to execute when a matching component is used
+    pub action: for<'a> fn(ComponentContext<'a, U, E>) -> BoxFuture<'a, Result<(), E>>,
+    /// Called before [`Self::action`]. The action is only executed if the check returns true.
+    /// Otherwise, [`crate::FrameworkError::ComponentCheckFailed`] is raised
+    pub check: Option<fn(ComponentContext<'_, U, E>) -> BoxFuture<'_, Result<bool, E>>>,
+}
+impl<U, E> Clone for ComponentHandler<U, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<U, E> Copy for ComponentHandler<U, E> {}
+
+impl<U, E> std::fmt::Debug for ComponentHandler<U, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self {
+            custom_id_prefix,
+            action,
+            check,
+        } = self;
+
+        f.debug_struct("ComponentHandler")
+            .field("custom_id_prefix", custom_id_prefix)
+            .field("action", &(*action as *const ()))
+            .field("check", &check.map(|f| f as *const ()))
+            .finish()
+    }
+}
+
+/// Context passed to [`ComponentHandler`] callbacks
+pub struct ComponentContext<'a, U, E> {
+    /// Serenity's context, like HTTP or cache
+    pub discord: &'a serenity::Context,
+    /// The component interaction which triggered the handler
+    pub interaction: &'a serenity::MessageComponentInteraction,
+    /// Keeps track of whether an initial response has been sent.
+    ///
+    /// Discord requires different HTTP endpoints for initial and additional responses.
+    pub has_sent_initial_response: &'a std::sync::atomic::AtomicBool,
+    /// Keeps track of whether the initial response was a new message, rather than an update of the
+    /// message which contains the component.
+    ///
+    /// Decides how [`Self::update_message`] edits the component's message after the initial
+    /// response.
+    pub has_sent_new_message: &'a std::sync::atomic::AtomicBool,
+    /// Read-only reference to the framework
+    pub framework: &'a crate::Framework<U, E>,
+    /// The handler whose prefix matched the interaction's `custom_id`
+    pub handler: &'a ComponentHandler<U, E>,
+    /// Your custom user data
+    pub data: &'a U,
+}
+impl<U, E> Clone for ComponentContext<'_, U, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<U, E> Copy for ComponentContext<'_, U, E> {}
+
+impl<U: std::fmt::Debug, E: std::fmt::Debug> std::fmt::Debug for ComponentContext<'_, U, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self {
+            discord: _,
+            interaction,
+            has_sent_initial_response,
+            has_sent_new_message,
+            framework: _,
+            handler,
+            data,
+        } = self;
+
+        f.debug_struct("ComponentContext")
+            .field("discord", &"<serenity Context>")
+            .field("interaction", interaction)
+            .field("has_sent_initial_response", has_sent_initial_response)
+            .field("has_sent_new_message", has_sent_new_message)
+            .field("framework", &"<poise Framework>")
+            .field("handler", handler)
+            .field("data", data)
+            .finish()
+    }
+}
+
+impl<'a, U, E> ComponentContext<'a, U, E> {
+    /// Returns the full `custom_id` of the used component
+    pub fn custom_id(self) -> &'a str {
+        &self.interaction.data.custom_id
+    }
+
+    /// Returns the part of the `custom_id` after the handler's
+    /// [`ComponentHandler::custom_id_prefix`]
+    pub fn custom_id_suffix(self) -> &'a str {
+        self.custom_id()
+            .strip_prefix(self.handler.custom_id_prefix)
+            .unwrap_or("")
+    }
+
+    /// Returns the values that were selected, if the component is a select menu
+    pub fn values(self) -> &'a [String] {
+        &self.interaction.data.values
+    }
+
+    /// Returns the user who used the component
+    pub fn author(self) -> &'a serenity::User {
+        &self.interaction.user
+    }
+
+    /// Returns the selected language of the user who used the component
+    pub fn locale(self) -> &'a str {
+        &self.interaction.locale
+    }
+
+    /// Returns whether this interaction has already been responded to
+    fn has_responded(self) -> bool {
+        self.has_sent_initial_response
+            .load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    /// Returns the [`crate::InteractionResponder`] that sends responses to this interaction
+    fn responder(self) -> crate::InteractionResponder<'a, U, E> {
+        crate::InteractionResponder {
+            framework: self.framework,
+            discord: self.discord,
+            interaction: crate::InteractionRef::Component(self.interaction),
+            has_sent_initial_response: self.has_sent_initial_response,
+            last_followup_id: None,
+        }
+    }
+
+    /// Sends a new message in response to the component interaction.
+    ///
+    /// If the interaction has already been responded to, a followup message is sent
+    pub async fn send<'b>(
+        self,
+        builder: impl for<'c> FnOnce(&'c mut crate::CreateReply<'b>) -> &'c mut crate::CreateReply<'b>,
+    ) -> Result<(), serenity::Error> {
+        let mut data = crate::CreateReply::default();
+        builder(&mut data);
+
+        if !self.has_responded() {
+            self.has_sent_new_message
+                .store(true, std::sync::atomic::Ordering::SeqCst);
+        }
+        self.responder().send_reply(data, false).await?;
+        Ok(())
+    }
+
+    /// Shorthand of [`Self::send`] for text-only messages
+    pub async fn say(self, text: impl Into<String>) -> Result<(), serenity::Error> {
+        self.send(|m| m.content(text)).await
+    }
+
+    /// Edits the message which contains the used component, for example to update a counter or to
+    /// disable the buttons.
+    ///
+    /// Fields which are not set in the builder are left unchanged
+    pub async fn update_message<'b>(
+        self,
+        builder: impl for<'c> FnOnce(&'c mut crate::CreateReply<'b>) -> &'c mut crate::CreateReply<'b>,
+    ) -> Result<(), serenity::Error> {
+        let mut data = crate::CreateReply::default();
+        builder(&mut data);
+        // Message updates can't be split, and attachments aren't supported
+        data.apply_overflow_policy(false, false);
+
+        let responder = self.responder();
+        if responder.claim_initial_response() {
+            let response = crate::InitialResponse::UpdateMessage(data);
+            return responder.send_initial_response(response).await;
+        }
+
+        let has_sent_new_message = self
+            .has_sent_new_message
+            .load(std::sync::atomic::Ordering::SeqCst);
+        if has_sent_new_message {
+            // The original response is a separate message, so the component's message has to be
+            // edited directly
+            let request = crate::MessageRequest::UpdateMessage {
+                channel_id: self.interaction.channel_id,
+                message_id: self.interaction.message.id,
+                reply: data,
+            };
+            self.framework.send_message(self.discord, request).await?;
+        } else {
+            // After a deferred update or an earlier update, the original response *is* the
+            // component's message
+            let request = crate::ResponseRequest::EditInitialResponse {
+                interaction: crate::InteractionRef::Component(self.interaction),
+                reply: data,
+            };
+            self.framework.send_response(self.discord, request).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Acknowledges the interaction without sending or changing anything yet. Use this if the
+    /// handler takes longer than three seconds to respond; the message can then be changed with
+    /// [`Self::update_message`]
+    pub async fn defer(self) -> Result<(), serenity::Error> {
+        let responder = self.responder();
+        if responder.claim_initial_response() {
+            let response = crate::InitialResponse::DeferredUpdate;
+            responder.send_initial_response(response).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the handler with the longest [`ComponentHandler::custom_id_prefix`] that matches the
+/// given `custom_id`
+pub(crate) fn find_component_handler<'a, U, E>(
+    handlers: &'a [ComponentHandler<U, E>],
+    custom_id: &str,
+) -> Option<&'a ComponentHandler<U, E>> {
+    handlers
+        .iter()
+        .filter(|handler| custom_id.starts_with(handler.custom_id_prefix))
+        .max_by_key(|handler| handler.custom_id_prefix.len())
+}
+
+#[test]
+fn test_find_component_handler() {
+    let handler = |custom_id_prefix| ComponentHandler::<(), ()> {
+        custom_id_prefix,
+        action: |_| Box::pin(async { Ok(()) }),
+        check: None,
+    };
+    let handlers = [handler("page:"), handler("page:next:"), handler("vote:")];
+
+    let find = |custom_id| find_component_handler(&handlers, custom_id).map(|h| h.custom_id_prefix);
+    assert_eq!(find("page:3"), Some("page:"));
+    assert_eq!(find("page:next:3"), Some("page:next:"));
+    assert_eq!(find("vote:yes"), Some("vote:"));
+    assert_eq!(find("other"), None);
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_component_send_and_update() {
+    use crate::testing::CapturedReplyKind;
+
+    let harness = crate::testing::TestHarness::new(
+        crate::FrameworkOptions::<(), serenity::Error> {
+            component_handlers: vec![ComponentHandler {
+                custom_id_prefix: "long:",
+                action: |ctx| {
+                    Box::pin(async move {
+                        ctx.say("a\n".repeat(1500)).await?;
+                        ctx.update_message(|m| m.content("clicked")).await
+                    })
+                },
+                check: None,
+            }],
+            ..Default::default()
+        },
+        (),
+    );
+
+    let msg = harness.message("buttons");
+    harness.click_component(&msg, "long:").await;
+    let replies = harness.take_replies();
+    let kinds = replies.iter().map(|reply| reply.kind).collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        [
+            CapturedReplyKind::InitialResponse,
+            CapturedReplyKind::Followup,
+            CapturedReplyKind::ComponentMessageUpdate,
+        ]
+    );
+    // The component's message is edited, not the new message sent in response
+    assert_eq!(replies[2].message_id, Some(msg.id));
+    assert_eq!(replies[2].content.as_deref(), Some("clicked"));
+}
@@ -2,10 +2,16 @@
 
 use crate::serenity_prelude as serenity;
 // I usually don't really do imports, but these are very convenient
-use crate::util::OrderedMap;
-use std::time::{Duration, Instant};
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
 
 /// Configuration struct for [`Cooldowns`]
+///
+/// By default, each bucket allows one invocation per configured duration. With the `*_uses` fields,
+/// a bucket allows multiple invocations per duration instead (e.g. 5 uses per 60 seconds). Uses
+/// are replenished continuously: with 5 uses per 60 seconds, one use becomes available every 12
+/// seconds. The `*_burst` fields allow additional invocations on top of that, if the bucket hasn't
+/// been used in a while.
 #[derive(Default, Clone, PartialEq, Eq, Debug, Hash)]
 pub struct CooldownConfig {
     /// This cooldown operates on a global basis
@@ -18,93 +24,684 @@ pub struct CooldownConfig {
     pub channel: Option<Duration>,
     /// This cooldown operates on a per-member basis
     pub member: Option<Duration>,
+
+    /// Number of invocations allowed per [`Self::global`] duration. Defaults to 1
+    pub global_uses: Option<u32>,
+    /// Number of invocations allowed per [`Self::user`] duration. Defaults to 1
+    pub user_uses: Option<u32>,
+    /// Number of invocations allowed per [`Self::guild`] duration. Defaults to 1
+    pub guild_uses: Option<u32>,
+    /// Number of invocations allowed per [`Self::channel`] duration. Defaults to 1
+    pub channel_uses: Option<u32>,
+    /// Number of invocations allowed per [`Self::member`] duration. Defaults to 1
+    pub member_uses: Option<u32>,
+
+    /// Additional invocations allowed in quick succession in the global bucket
+    pub global_burst: u32,
+    /// Additional invocations allowed in quick succession in the per-user bucket
+    pub user_burst: u32,
+    /// Additional invocations allowed in quick succession in the per-guild bucket
+    pub guild_burst: u32,
+    /// Additional invocations allowed in quick succession in the per-channel bucket
+    pub channel_burst: u32,
+    /// Additional invocations allowed in quick succession in the per-member bucket
+    pub member_burst: u32,
+}
+
+/// Rate limit of a single bucket, derived from [`CooldownConfig`]
+///
+/// Implemented as a generic cell rate algorithm: the stored timestamp is the point in time at
+/// which the bucket would be fully replenished. Every invocation pushes it back by one
+/// interval (`window / uses`); invocations are allowed as long as it's less than the burst
+/// tolerance in the future.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct BucketLimit {
+    /// Duration of the window
+    pub window: Duration,
+    /// Invocations per window
+    pub uses: u32,
+    /// Additional invocations on top of `uses`
+    pub burst: u32,
+}
+
+impl BucketLimit {
+    /// Time it takes for a single use to be replenished
+    fn interval(self) -> Duration {
+        self.window / self.uses.max(1)
+    }
+
+    /// How far the replenish timestamp may lie in the future for an invocation to be allowed
+    fn tolerance(self) -> Duration {
+        self.interval() * (self.uses.max(1) + self.burst - 1)
+    }
+
+    /// Number of invocations which are allowed in quick succession when the bucket is fully
+    /// replenished
+    fn capacity(self) -> u32 {
+        self.uses.max(1) + self.burst
+    }
+
+    /// Number of invocations which are currently allowed, given the stored replenish timestamp
+    fn remaining_uses(self, replenished_at: Option<SystemTime>, now: SystemTime) -> u32 {
+        let replenished_at = match replenished_at {
+            Some(x) => x,
+            None => return self.capacity(),
+        };
+        let backlog = match replenished_at.duration_since(now) {
+            Ok(backlog) => backlog,
+            Err(_) => return self.capacity(),
+        };
+
+        // Every started interval of backlog is one use that hasn't been replenished yet
+        let interval = self.interval().as_nanos().max(1);
+        let mut used = backlog.as_nanos() / interval;
+        if backlog.as_nanos() % interval != 0 {
+            used += 1;
+        }
+        self.capacity().saturating_sub(used as u32)
+    }
+
+    /// Returns how long to wait until the next invocation is allowed, or None if it's allowed now
+    fn remaining_cooldown(self, replenished_at: SystemTime, now: SystemTime) -> Option<Duration> {
+        let allowed_at = replenished_at.checked_sub(self.tolerance())?;
+        allowed_at
+            .duration_since(now)
+            .ok()
+            .filter(|d| *d > Duration::from_secs(0))
+    }
+
+    /// Returns the new replenish timestamp after an invocation
+    fn consume(self, replenished_at: Option<SystemTime>, now: SystemTime) -> SystemTime {
+        let start = match replenished_at {
+            Some(replenished_at) if replenished_at > now => replenished_at,
+            _ => now,
+        };
+        start + self.interval()
+    }
+
+    /// Returns the replenish timestamp after undoing a single invocation, or None if the bucket
+    /// would be fully replenished
+    fn refund(self, replenished_at: SystemTime, now: SystemTime) -> Option<SystemTime> {
+        replenished_at
+            .checked_sub(self.interval())
+            .filter(|refunded| *refunded > now)
+    }
+}
+
+/// Describes the cooldown that prevented an invocation, see [`Cooldowns::remaining_cooldown`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct CooldownExceeded {
+    /// Time until the command can be invoked again
+    pub remaining_cooldown: Duration,
+    /// The bucket whose cooldown was hit. If multiple buckets are on cooldown, it's the one with
+    /// the longest remaining cooldown
+    pub bucket: CooldownBucket,
+    /// Number of uses left in the hit bucket
+    pub remaining_uses: u32,
+    /// Number of uses the hit bucket allows in quick succession when it's fully replenished
+    /// ([`CooldownConfig`]'s uses plus burst). One use returns after `remaining_cooldown`, the
+    /// rest one by one at the bucket's rate
+    pub capacity: u32,
+}
+
+/// Identifies whose cooldown is meant: a cooldown can apply to everyone, or to single users,
+/// guilds, channels or guild members
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum CooldownBucket {
+    /// The cooldown applies to all invocations
+    Global,
+    /// The cooldown applies to invocations by this user
+    User(serenity::UserId),
+    /// The cooldown applies to invocations in this guild
+    Guild(serenity::GuildId),
+    /// The cooldown applies to invocations in this channel
+    Channel(serenity::ChannelId),
+    /// The cooldown applies to invocations by this user in this guild
+    Member(serenity::UserId, serenity::GuildId),
+}
+
+/// Checks the given buckets against their stored replenish timestamps, which are given in the same
+/// order. If none of the buckets is on cooldown, returns their new replenish timestamps after
+/// one invocation. Otherwise, returns the longest cooldown.
+///
+/// This is the calculation behind [`CooldownStore::try_acquire`], for stores which implement it
+/// themselves
+pub fn acquire_cooldown(
+    limits: &[(CooldownBucket, BucketLimit)],
+    replenished_at: &[Option<SystemTime>],
+    now: SystemTime,
+) -> Result<Vec<SystemTime>, CooldownExceeded> {
+    let mut exceeded: Option<CooldownExceeded> = None;
+    for (&(bucket, limit), &replenished_at) in limits.iter().zip(replenished_at) {
+        let remaining_cooldown = match replenished_at {
+            Some(replenished_at) => limit.remaining_cooldown(replenished_at, now),
+            None => None,
+        };
+        let remaining_cooldown = match remaining_cooldown {
+            Some(x) => x,
+            None => continue,
+        };
+        match exceeded {
+            Some(e) if e.remaining_cooldown >= remaining_cooldown => {}
+            _ => {
+                exceeded = Some(CooldownExceeded {
+                    remaining_cooldown,
+                    bucket,
+                    remaining_uses: limit.remaining_uses(replenished_at, now),
+                    capacity: limit.capacity(),
+                })
+            }
+        }
+    }
+    if let Some(exceeded) = exceeded {
+        return Err(exceeded);
+    }
+
+    Ok(limits
+        .iter()
+        .zip(replenished_at)
+        .map(|(&(_, limit), &replenished_at)| limit.consume(replenished_at, now))
+        .collect())
+}
+
+/// Storage backend for cooldowns.
+///
+/// For every command (identified by its [qualified name](crate::Command::qualified_name)) and
+/// bucket, the store holds a wall-clock timestamp at which the bucket is fully replenished, i.e.
+/// until which it's (at least partially) on cooldown. Because the timestamps are wall-clock based,
+/// they stay meaningful when stored in a file or database and loaded again after a restart, or by
+/// a different shard process.
+///
+/// The default store, [`InMemoryCooldownStore`], keeps everything in memory. Store a different
+/// implementation in [`crate::FrameworkOptions::cooldown_store`] to persist cooldowns.
+///
+/// Implementations can't fail; if the backing storage is unavailable, it's usually best to log
+/// the error and behave as if there was no cooldown.
+#[async_trait::async_trait]
+pub trait CooldownStore: Send + Sync {
+    /// Returns the point in time until which the given bucket of the given command is on cooldown,
+    /// if one was stored
+    async fn get(&self, command: &str, bucket: CooldownBucket) -> Option<SystemTime>;
+
+    /// Stores the point in time until which the given bucket of the given command is on cooldown
+    async fn set(&self, command: &str, bucket: CooldownBucket, until: SystemTime);
+
+    /// Removes the cooldown of the given bucket of the given command
+    ///
+    /// The default implementation stores an expired timestamp
+    async fn remove(&self, command: &str, bucket: CooldownBucket) {
+        self.set(command, bucket, SystemTime::UNIX_EPOCH).await;
+    }
+
+    /// Checks whether any of the given buckets of the given command is on cooldown. If none is,
+    /// one invocation is counted towards each of them; otherwise nothing is changed.
+    ///
+    /// The framework calls this for every invocation. Check and update must happen atomically, or
+    /// concurrent invocations (possibly from different processes sharing the store) could all
+    /// pass the check before any of them is counted. Use [`acquire_cooldown`] for the calculation.
+    ///
+    /// The default implementation is built on [`Self::get`] and [`Self::set`] and is therefore
+    /// not atomic
+    async fn try_acquire(
+        &self,
+        command: &str,
+        limits: &[(CooldownBucket, BucketLimit)],
+        now: SystemTime,
+    ) -> Result<(), CooldownExceeded> {
+        let mut replenished_at = Vec::with_capacity(limits.len());
+        for &(bucket, _) in limits {
+            replenished_at.push(self.get(command, bucket).await);
+        }
+        let updated = acquire_cooldown(limits, &replenished_at, now)?;
+        for (&(bucket, _), until) in limits.iter().zip(updated) {
+            self.set(command, bucket, until).await;
+        }
+        Ok(())
+    }
+
+    /// Evicts all entries whose cooldown has expired. Called periodically by the framework
+    ///
+    /// The default implementation does nothing, which is appropriate for stores that expire
+    /// entries on their own (e.g. databases with a TTL feature)
+    async fn purge(&self) {}
+}
+
+/// The default [`CooldownStore`]. Cooldowns are kept in memory and reset on restart
+///
+/// Lookups are hash-based. Expired entries are evicted by [`CooldownStore::purge`], which
+/// [`crate::Framework`] calls periodically, so memory usage is bounded by the number of buckets
+/// that are actually on cooldown.
+#[derive(Default, Debug)]
+pub struct InMemoryCooldownStore {
+    /// Cooldown expiry timestamps, keyed by command qualified name and bucket
+    cooldowns: std::sync::Mutex<HashMap<String, HashMap<CooldownBucket, SystemTime>>>,
+}
+
+impl InMemoryCooldownStore {
+    /// Create a new, empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of stored buckets, across all commands
+    pub fn len(&self) -> usize {
+        self.cooldowns
+            .lock()
+            .unwrap()
+            .values()
+            .map(|c| c.len())
+            .sum()
+    }
+
+    /// Returns true if no buckets are stored
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[async_trait::async_trait]
+impl CooldownStore for InMemoryCooldownStore {
+    async fn get(&self, command: &str, bucket: CooldownBucket) -> Option<SystemTime> {
+        self.cooldowns
+            .lock()
+            .unwrap()
+            .get(command)?
+            .get(&bucket)
+            .copied()
+    }
+
+    async fn set(&self, command: &str, bucket: CooldownBucket, until: SystemTime) {
+        let mut cooldowns = self.cooldowns.lock().unwrap();
+        match cooldowns.get_mut(command) {
+            Some(buckets) => {
+                buckets.insert(bucket, until);
+            }
+            None => {
+                let mut buckets = HashMap::new();
+                buckets.insert(bucket, until);
+                cooldowns.insert(command.to_owned(), buckets);
+            }
+        }
+    }
+
+    async fn remove(&self, command: &str, bucket: CooldownBucket) {
+        if let Some(buckets) = self.cooldowns.lock().unwrap().get_mut(command) {
+            buckets.remove(&bucket);
+        }
+    }
+
+    async fn try_acquire(
+        &self,
+        command: &str,
+        limits: &[(CooldownBucket, BucketLimit)],
+        now: SystemTime,
+    ) -> Result<(), CooldownExceeded> {
+        let mut cooldowns = self.cooldowns.lock().unwrap();
+        let replenished_at = limits
+            .iter()
+            .map(|(bucket, _)| cooldowns.get(command)?.get(bucket).copied())
+            .collect::<Vec<_>>();
+        let updated = acquire_cooldown(limits, &replenished_at, now)?;
+
+        let buckets = cooldowns.entry(command.to_owned()).or_default();
+        for (&(bucket, _), until) in limits.iter().zip(updated) {
+            buckets.insert(bucket, until);
+        }
+        Ok(())
+    }
+
+    async fn purge(&self) {
+        let now = SystemTime::now();
+        self.cooldowns.lock().unwrap().retain(|_, buckets| {
+            buckets.retain(|_, until| *until > now);
+            !buckets.is_empty()
+        });
+    }
 }
 
 /// Handles cooldowns for a single command
 ///
 /// You probably don't need to use this directly. `#[poise::command]` automatically generates a
 /// cooldown handler.
+///
+/// The cooldown state itself lives in [`crate::FrameworkOptions::cooldown_store`].
 #[derive(Default, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Cooldowns {
     /// Stores the cooldown durations
     cooldown: CooldownConfig,
-
-    /// Stores the timestamp of the last global invocation
-    global_invocation: Option<Instant>,
-    /// Stores the timestamps of the last invocation per user
-    user_invocations: OrderedMap<serenity::UserId, Instant>,
-    /// Stores the timestamps of the last invocation per guild
-    guild_invocations: OrderedMap<serenity::GuildId, Instant>,
-    /// Stores the timestamps of the last invocation per channel
-    channel_invocations: OrderedMap<serenity::ChannelId, Instant>,
-    /// Stores the timestamps of the last invocation per member (user and guild)
-    member_invocations: OrderedMap<(serenity::UserId, serenity::GuildId), Instant>,
 }
 
 impl Cooldowns {
     /// Create a new cooldown handler with the given cooldown durations
     pub fn new(config: CooldownConfig) -> Self {
-        Self {
-            cooldown: config,
-
-            global_invocation: None,
-            user_invocations: OrderedMap::new(),
-            guild_invocations: OrderedMap::new(),
-            channel_invocations: OrderedMap::new(),
-            member_invocations: OrderedMap::new(),
-        }
+        Self { cooldown: config }
     }
 
-    /// Queries the cooldown buckets and checks if all cooldowns have expired and command
-    /// execution may proceed. If not, Some is returned with the remaining cooldown
-    pub fn remaining_cooldown<U, E>(&self, ctx: crate::Context<'_, U, E>) -> Option<Duration> {
-        let mut cooldown_data = vec![
-            (self.cooldown.global, self.global_invocation),
+    /// Returns the configured cooldown durations, e.g. to display them in a help menu
+    pub fn config(&self) -> &CooldownConfig {
+        &self.cooldown
+    }
+
+    /// Returns all buckets that apply to this invocation, along with their configured limits
+    fn buckets<U, E>(&self, ctx: crate::Context<'_, U, E>) -> Vec<(CooldownBucket, BucketLimit)> {
+        let c = &self.cooldown;
+        let mut buckets = vec![
             (
-                self.cooldown.user,
-                self.user_invocations.get(&ctx.author().id).copied(),
+                c.global,
+                c.global_uses,
+                c.global_burst,
+                CooldownBucket::Global,
             ),
             (
-                self.cooldown.channel,
-                self.channel_invocations.get(&ctx.channel_id()).copied(),
+                c.user,
+                c.user_uses,
+                c.user_burst,
+                CooldownBucket::User(ctx.author().id),
+            ),
+            (
+                c.channel,
+                c.channel_uses,
+                c.channel_burst,
+                CooldownBucket::Channel(ctx.channel_id()),
             ),
         ];
 
         if let Some(guild_id) = ctx.guild_id() {
-            cooldown_data.push((
-                self.cooldown.guild,
-                self.guild_invocations.get(&guild_id).copied(),
+            buckets.push((
+                c.guild,
+                c.guild_uses,
+                c.guild_burst,
+                CooldownBucket::Guild(guild_id),
             ));
-            cooldown_data.push((
-                self.cooldown.member,
-                self.member_invocations
-                    .get(&(ctx.author().id, guild_id))
-                    .copied(),
+            buckets.push((
+                c.member,
+                c.member_uses,
+                c.member_burst,
+                CooldownBucket::Member(ctx.author().id, guild_id),
             ));
         }
 
-        cooldown_data
-            .iter()
-            .filter_map(|&(cooldown, last_invocation)| {
-                let duration_since = Instant::now().saturating_duration_since(last_invocation?);
-                let cooldown_left = cooldown?.checked_sub(duration_since)?;
-                Some(cooldown_left)
+        buckets
+            .into_iter()
+            .filter_map(|(window, uses, burst, bucket)| {
+                let limit = BucketLimit {
+                    window: window?,
+                    uses: uses.unwrap_or(1),
+                    burst,
+                };
+                Some((bucket, limit))
             })
-            .max()
+            .collect()
+    }
+
+    /// Queries the cooldown buckets and checks if all cooldowns have expired and command
+    /// execution may proceed. If not, Some is returned with the remaining cooldown and the bucket
+    /// that's responsible
+    pub async fn remaining_cooldown<U, E>(
+        &self,
+        ctx: crate::Context<'_, U, E>,
+    ) -> Option<CooldownExceeded> {
+        let store = &ctx.framework().options().cooldown_store;
+        let command = &ctx.command().qualified_name;
+
+        let limits = self.buckets(ctx);
+        let mut replenished_at = Vec::with_capacity(limits.len());
+        for &(bucket, _) in &limits {
+            replenished_at.push(store.get(command, bucket).await);
+        }
+        acquire_cooldown(&limits, &replenished_at, SystemTime::now()).err()
     }
 
     /// Indicates that a command has been executed and all associated cooldowns should start running
-    pub fn start_cooldown<U, E>(&mut self, ctx: crate::Context<'_, U, E>) {
-        let now = Instant::now();
+    ///
+    /// Unlike [`Self::try_start_cooldown`], this counts the invocation even if a cooldown is
+    /// already active
+    pub async fn start_cooldown<U, E>(&self, ctx: crate::Context<'_, U, E>) {
+        let store = &ctx.framework().options().cooldown_store;
+        let command = &ctx.command().qualified_name;
 
-        self.global_invocation = Some(now);
-        self.user_invocations.insert(ctx.author().id, now);
-        self.channel_invocations.insert(ctx.channel_id(), now);
+        let now = SystemTime::now();
+        for (bucket, limit) in self.buckets(ctx) {
+            let replenished_at = store.get(command, bucket).await;
+            store
+                .set(command, bucket, limit.consume(replenished_at, now))
+                .await;
+        }
+    }
 
-        if let Some(guild_id) = ctx.guild_id() {
-            self.guild_invocations.insert(guild_id, now);
-            self.member_invocations
-                .insert((ctx.author().id, guild_id), now);
+    /// Checks the cooldowns and, if none of them is active, starts them, in a single atomic
+    /// [`CooldownStore::try_acquire`] call. This is what the framework does before running a
+    /// command
+    ///
+    /// Returns Ok(false) if no cooldown applies to this invocation, so nothing was started
+    pub async fn try_start_cooldown<U, E>(
+        &self,
+        ctx: crate::Context<'_, U, E>,
+    ) -> Result<bool, CooldownExceeded> {
+        let store = &ctx.framework().options().cooldown_store;
+        let command = &ctx.command().qualified_name;
+
+        let buckets = self.buckets(ctx);
+        if buckets.is_empty() {
+            return Ok(false);
+        }
+        store
+            .try_acquire(command, &buckets, SystemTime::now())
+            .await?;
+        Ok(true)
+    }
+
+    /// Undoes a single [`Self::start_cooldown`], for example because the command failed or was
+    /// cancelled. The invocation doesn't count towards any of the buckets anymore
+    pub async fn refund_cooldown<U, E>(&self, ctx: crate::Context<'_, U, E>) {
+        let store = &ctx.framework().options().cooldown_store;
+        let command = &ctx.command().qualified_name;
+
+        let now = SystemTime::now();
+        for (bucket, limit) in self.buckets(ctx) {
+            let replenished_at = match store.get(command, bucket).await {
+                Some(x) => x,
+                None => continue,
+            };
+            match limit.refund(replenished_at, now) {
+                Some(refunded) => store.set(command, bucket, refunded).await,
+                None => store.remove(command, bucket).await,
+            }
         }
     }
+
+    /// Clears all cooldown buckets that apply to this invocation, as if the command had never been
+    /// used
+    pub async fn reset_cooldown<U, E>(&self, ctx: crate::Context<'_, U, E>) {
+        let store = &ctx.framework().options().cooldown_store;
+        let command = &ctx.command().qualified_name;
+
+        for (bucket, _) in self.buckets(ctx) {
+            store.remove(command, bucket).await;
+        }
+    }
+}
+
+/// The invocations whose cooldown was started by the framework and hasn't been refunded yet, keyed
+/// by [`crate::Context::id`]. Makes sure that [`crate::Context::refund_cooldown`] only refunds
+/// what was actually started, and only once
+#[derive(Default, Debug)]
+pub(crate) struct StartedCooldowns(std::sync::Mutex<HashMap<u64, std::time::Instant>>);
+
+impl StartedCooldowns {
+    /// How long after the invocation its cooldown can still be refunded
+    const REFUND_WINDOW: Duration = Duration::from_secs(3600);
+
+    /// Records that the cooldown of the given invocation was started
+    pub(crate) fn insert(&self, invocation_id: u64) {
+        self.0
+            .lock()
+            .unwrap()
+            .insert(invocation_id, std::time::Instant::now());
+    }
+
+    /// Removes the given invocation. Returns true if its cooldown was started and not taken yet
+    pub(crate) fn take(&self, invocation_id: u64) -> bool {
+        self.0.lock().unwrap().remove(&invocation_id).is_some()
+    }
+
+    /// Evicts invocations that are too old to be refunded. Called periodically by the framework
+    pub(crate) fn purge(&self) {
+        self.0
+            .lock()
+            .unwrap()
+            .retain(|_, started| started.elapsed() < Self::REFUND_WINDOW);
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_in_memory_cooldown_store() {
+    let store = InMemoryCooldownStore::new();
+    let user = CooldownBucket::User(serenity::UserId(1));
+    let until = SystemTime::now() + Duration::from_secs(60);
+
+    assert_eq!(store.get("ping", user).await, None);
+    store.set("ping", user, until).await;
+    assert_eq!(store.get("ping", user).await, Some(until));
+    assert_eq!(store.get("pong", user).await, None);
+    assert_eq!(store.get("ping", CooldownBucket::Global).await, None);
+
+    // Only expired entries are evicted
+    let expired = CooldownBucket::User(serenity::UserId(2));
+    store.set("ping", expired, SystemTime::now()).await;
+    store.set("pong", expired, SystemTime::now()).await;
+    assert_eq!(store.len(), 3);
+    store.purge().await;
+    assert_eq!(store.len(), 1);
+    assert_eq!(store.get("ping", user).await, Some(until));
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_try_acquire() {
+    let store = InMemoryCooldownStore::new();
+    let now = SystemTime::now();
+    let limit = |uses| BucketLimit {
+        window: Duration::from_secs(60),
+        uses,
+        burst: 0,
+    };
+    let user = CooldownBucket::User(serenity::UserId(1));
+    let limits = [(user, limit(1)), (CooldownBucket::Global, limit(3))];
+
+    assert_eq!(store.try_acquire("ping", &limits, now).await, Ok(()));
+    let global_after_first = store.get("ping", CooldownBucket::Global).await;
+
+    // The user bucket is on cooldown, so the global bucket must not be counted either
+    let exceeded = store.try_acquire("ping", &limits, now).await.unwrap_err();
+    assert_eq!(exceeded.bucket, user);
+    assert_eq!(exceeded.remaining_cooldown, Duration::from_secs(60));
+    assert_eq!(exceeded.remaining_uses, 0);
+    assert_eq!(exceeded.capacity, 1);
+    assert_eq!(
+        store.get("ping", CooldownBucket::Global).await,
+        global_after_first
+    );
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_refund_cooldown_once() {
+    let harness = crate::testing::TestHarness::new(
+        crate::FrameworkOptions {
+            commands: vec![crate::Command::<(), String> {
+                name: "ping",
+                qualified_name: "ping".into(),
+                cooldowns: Cooldowns::new(CooldownConfig {
+                    user: Some(Duration::from_secs(60)),
+                    user_uses: Some(2),
+                    ..Default::default()
+                }),
+                prefix_action: Some(|ctx, args| {
+                    Box::pin(async move {
+                        if args == "refund" {
+                            // Only the first refund has an effect
+                            crate::Context::Prefix(ctx).refund_cooldown().await;
+                            crate::Context::Prefix(ctx).refund_cooldown().await;
+                        }
+                        let _ = crate::say_reply(ctx.into(), "pong").await;
+                        Ok(())
+                    })
+                }),
+                ..Default::default()
+            }],
+            prefix_options: crate::PrefixFrameworkOptions {
+                prefix: Some("~".into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        (),
+    );
+
+    for content in &["~ping", "~ping refund", "~ping", "~ping"] {
+        harness.send_message(*content).await;
+    }
+    let pongs = harness
+        .take_replies()
+        .into_iter()
+        .filter(|reply| reply.content.as_deref() == Some("pong"))
+        .count();
+    assert_eq!(pongs, 3);
+}
+
+#[cfg(test)]
+#[test]
+fn test_bucket_limit() {
+    let now = SystemTime::now();
+    let limit = BucketLimit {
+        window: Duration::from_secs(60),
+        uses: 5,
+        burst: 1,
+    };
+
+    // Six invocations (5 uses + 1 burst) are allowed right away, then one every 12 seconds
+    let mut replenished_at = None;
+    assert_eq!(limit.capacity(), 6);
+    assert_eq!(limit.remaining_uses(replenished_at, now), 6);
+    for remaining_uses in (0..6).rev() {
+        if let Some(replenished_at) = replenished_at {
+            assert_eq!(limit.remaining_cooldown(replenished_at, now), None);
+        }
+        replenished_at = Some(limit.consume(replenished_at, now));
+        assert_eq!(limit.remaining_uses(replenished_at, now), remaining_uses);
+    }
+    let replenished_at = replenished_at.unwrap();
+    assert_eq!(
+        limit.remaining_cooldown(replenished_at, now),
+        Some(Duration::from_secs(12))
+    );
+    assert_eq!(
+        limit.remaining_cooldown(replenished_at, now + Duration::from_secs(12)),
+        None
+    );
+    // A refund makes exactly one more use available
+    let refunded = limit.refund(replenished_at, now).unwrap();
+    assert_eq!(limit.remaining_cooldown(refunded, now), None);
+    assert_eq!(limit.remaining_uses(Some(refunded), now), 1);
+    let consumed = limit.consume(Some(refunded), now);
+    assert_eq!(
+        limit.remaining_cooldown(consumed, now),
+        Some(Duration::from_secs(12))
+    );
+
+    // A single use per window behaves like a classic cooldown
+    let limit = BucketLimit {
+        window: Duration::from_secs(10),
+        uses: 1,
+        burst: 0,
+    };
+    let replenished_at = limit.consume(None, now);
+    assert_eq!(replenished_at, now + Duration::from_secs(10));
+    assert_eq!(
+        limit.remaining_cooldown(replenished_at, now + Duration::from_secs(4)),
+        Some(Duration::from_secs(6))
+    );
+    assert_eq!(limit.refund(replenished_at, now), None);
 }
@@ -15,6 +15,10 @@ where
 macro_rules! event {
     ($lt1:lifetime $(
         $fn_name:ident $(<$lt2:lifetime>)? => $variant_name:ident { $( $arg_name:ident: $arg_type:ty ),* },
+    )*
+    ;
+    $(
+        $(#[cfg($cfg:meta)])? $custom_variant_name:ident { $( $custom_arg_name:ident: $custom_arg_type:ty ),* },
     )*) => {
         #[serenity::async_trait]
         impl<F> serenity::EventHandler for EventWrapper<F>
@@ -28,7 +32,9 @@ macro_rules! event {
             )*
         }
 
-        /// This enum stores every possible event that a [`serenity::EventHandler`] can receive.
+        /// This enum stores every possible event that a [`serenity::EventHandler`] can receive, plus
+        /// the events emitted by the framework itself, like the track notifications of a music
+        /// queue (with the `voice` feature).
         ///
         /// Passed to the stored callback by [`EventWrapper`].
         #[allow(clippy::large_enum_variant)]
@@ -38,6 +44,9 @@ macro_rules! event {
             $(
                 $variant_name { $( $arg_name: $arg_type ),* },
             )*
+            $(
+                $(#[cfg($cfg)])? $custom_variant_name { $( $custom_arg_name: $custom_arg_type ),* },
+            )*
         }
 
         impl Event<'_> {
@@ -47,6 +56,9 @@ macro_rules! event {
                     $(
                         Self::$variant_name { .. } => stringify!($variant_name),
                     )*
+                    $(
+                        $(#[cfg($cfg)])? Self::$custom_variant_name { .. } => stringify!($custom_variant_name),
+                    )*
                 }
             }
         }
@@ -100,4 +112,8 @@ event! {
     voice_state_update => VoiceStateUpdate { old: Option<serenity::VoiceState>, new: serenity::VoiceState },
     webhook_update => WebhookUpdate { guild_id: serenity::GuildId, belongs_to_channel_id: serenity::ChannelId },
     interaction_create => InteractionCreate { interaction: serenity::Interaction },
+    ;
+    // Emitted by the framework itself
+    #[cfg(feature = "voice")] TrackStart { guild_id: serenity::GuildId, track: songbird::tracks::TrackHandle },
+    #[cfg(feature = "voice")] TrackEnd { guild_id: serenity::GuildId, track: songbird::tracks::TrackHandle },
 }
@@ -3,6 +3,16 @@
 use crate::serenity_prelude as serenity;
 use crate::BoxFuture;
 
+/// Where application commands are registered when using [`FrameworkBuilder::register_commands`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CommandRegistrationScope {
+    /// Register the commands globally. Global commands may take up to an hour to show up
+    Global,
+    /// Register the commands in each of the given guilds. Changes show up instantly, which makes
+    /// this useful for development guilds
+    Guilds(Vec<serenity::GuildId>),
+}
+
 /// A builder to configure and run a framework.
 ///
 /// If one of the following required values is missing, the builder will panic on start:
@@ -35,6 +45,8 @@ pub struct FrameworkBuilder<U, E> {
     commands: Vec<crate::Command<U, E>>,
     /// See [`Self::initialize_owners()`]
     initialize_owners: bool,
+    /// See [`Self::register_commands()`]
+    register_commands: Option<CommandRegistrationScope>,
 }
 
 impl<U, E> Default for FrameworkBuilder<U, E> {
@@ -46,6 +58,7 @@ impl<U, E> Default for FrameworkBuilder<U, E> {
             token: Default::default(),
             commands: Default::default(),
             initialize_owners: true,
+            register_commands: None,
         }
     }
 }
@@ -151,6 +164,31 @@ impl<U, E> FrameworkBuilder<U, E> {
         self
     }
 
+    /// Register all application commands automatically on the first Ready event, either globally
+    /// or in a set of (development) guilds.
+    ///
+    /// Only the commands which changed since the last registration are sent to Discord, see
+    /// [`crate::builtins::sync_application_commands`]. Registration runs in the background, so
+    /// the Ready event isn't delayed by it. If registration fails,
+    /// [`crate::FrameworkError::CommandRegistration`] is passed to
+    /// [`crate::FrameworkOptions::on_error`].
+    ///
+    /// ```rust
+    /// # use poise::serenity_prelude as serenity;
+    /// poise::Framework::<(), ()>::build()
+    ///     // framework setup...
+    ///     .register_commands(poise::CommandRegistrationScope::Guilds(vec![
+    ///         serenity::GuildId(123456789),
+    ///     ]))
+    ///     // framework startup...
+    /// # ;
+    /// ```
+    #[must_use]
+    pub fn register_commands(mut self, scope: CommandRegistrationScope) -> Self {
+        self.register_commands = Some(scope);
+        self
+    }
+
     /// Build the framework with the specified configuration.
     ///
     /// For more information, see [`FrameworkBuilder`]
@@ -194,7 +232,9 @@ impl<U, E> FrameworkBuilder<U, E> {
         }
 
         // Create framework with specified settings
-        crate::Framework::new(client_builder, user_data_setup, options).await
+        let framework = crate::Framework::new(client_builder, user_data_setup, options).await?;
+        *framework.command_registration.lock().unwrap() = self.register_commands;
+        Ok(framework)
     }
 
     /// Start the framework with the specified configuration.
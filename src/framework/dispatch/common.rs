@@ -1,6 +1,7 @@
 //! Prefix and slash agnostic utilities for dispatching incoming events onto framework commands
 
 use crate::serenity_prelude as serenity;
+use tracing::Instrument as _;
 
 /// Retrieves user permissions in the given channel. If unknown, returns None. If in DMs, returns
 /// `Permissions::all()`.
@@ -24,8 +25,10 @@ async fn user_permissions(
     let channel = match channel_id.to_channel(ctx).await {
         Ok(serenity::Channel::Guild(channel)) => channel,
         Ok(_other_channel) => {
-            println!(
-                "Warning: guild message was supposedly sent in a non-guild channel. Denying invocation"
+            tracing::warn!(
+                guild_id = guild_id.0,
+                channel_id = channel_id.0,
+                "guild message was supposedly sent in a non-guild channel, denying invocation"
             );
             return None;
         }
@@ -60,11 +63,100 @@ async fn missing_permissions<U, E>(
     Some(required_permissions - permissions?)
 }
 
+/// Creates the span that a command invocation runs in. The `command` field is left empty, to be
+/// recorded as soon as the invoked command is known
+pub fn command_span(
+    kind: &'static str,
+    guild_id: Option<serenity::GuildId>,
+    channel_id: serenity::ChannelId,
+    user_id: serenity::UserId,
+) -> tracing::Span {
+    tracing::info_span!(
+        "command",
+        kind,
+        command = tracing::field::Empty,
+        guild_id = guild_id.map(|id| id.0),
+        channel_id = channel_id.0,
+        user_id = user_id.0,
+    )
+}
+
+/// Short machine-readable description of how an invocation failed, for logging
+fn error_outcome<U, E>(error: &crate::FrameworkError<'_, U, E>) -> &'static str {
+    match error {
+        crate::FrameworkError::Command { .. } => "error",
+        crate::FrameworkError::ArgumentParse { .. } => "argument_parse",
+        crate::FrameworkError::CommandStructureMismatch { .. } => "structure_mismatch",
+        crate::FrameworkError::CooldownHit { .. } => "cooldown_hit",
+        crate::FrameworkError::MissingBotPermissions { .. } => "missing_bot_permissions",
+        crate::FrameworkError::MissingUserPermissions { .. } => "missing_user_permissions",
+        crate::FrameworkError::NotAnOwner { .. } => "not_an_owner",
+        crate::FrameworkError::NotInVoiceChannel { .. } => "not_in_voice_channel",
+        crate::FrameworkError::CommandCheckFailed { .. } => "check_failed",
+        crate::FrameworkError::ComponentHandler { .. } => "error",
+        crate::FrameworkError::ComponentCheckFailed { .. } => "check_failed",
+        crate::FrameworkError::Setup { .. }
+        | crate::FrameworkError::CommandRegistration { .. }
+        | crate::FrameworkError::Listener { .. }
+        | crate::FrameworkError::UnknownCommand { .. } => "other",
+    }
+}
+
+/// Emits an event with the latency and outcome of a finished command invocation. Should be called
+/// within the invocation's [`command_span`]
+pub fn log_invocation_outcome<U, E>(
+    start: std::time::Instant,
+    result: &Result<(), Option<(crate::FrameworkError<'_, U, E>, &crate::Command<U, E>)>>,
+) {
+    let outcome = match result {
+        Ok(()) => "success",
+        Err(None) => "not_executed",
+        Err(Some((error, _))) => error_outcome(error),
+    };
+    log_outcome(start, outcome);
+}
+
+/// Like [`log_invocation_outcome`], but for component handler invocations
+pub fn log_component_outcome<U, E>(
+    start: std::time::Instant,
+    result: &Result<(), crate::FrameworkError<'_, U, E>>,
+) {
+    let outcome = match result {
+        Ok(()) => "success",
+        Err(error) => error_outcome(error),
+    };
+    log_outcome(start, outcome);
+}
+
+/// Emits the event of [`log_invocation_outcome`] and [`log_component_outcome`]
+fn log_outcome(start: std::time::Instant, outcome: &'static str) {
+    let latency_ms = start.elapsed().as_millis() as u64;
+    tracing::info!(outcome, latency_ms, "command invocation finished");
+}
+
 /// Checks if the invoker is allowed to execute this command at this point in time
 #[allow(clippy::needless_lifetimes)] // false positive (clippy issue 7271)
 pub async fn check_permissions_and_cooldown<'a, U, E>(
     ctx: crate::Context<'a, U, E>,
     cmd: &crate::Command<U, E>,
+) -> Result<(), crate::FrameworkError<'a, U, E>> {
+    let result = run_checks(ctx, cmd)
+        .instrument(tracing::debug_span!("checks"))
+        .await;
+    if let Err(error) = &result {
+        tracing::debug!(
+            outcome = error_outcome(error),
+            "invocation was rejected by checks"
+        );
+    }
+    result
+}
+
+/// Runs all permission checks, command checks and cooldowns, see [`check_permissions_and_cooldown`]
+#[allow(clippy::needless_lifetimes)] // false positive (clippy issue 7271)
+async fn run_checks<'a, U, E>(
+    ctx: crate::Context<'a, U, E>,
+    cmd: &crate::Command<U, E>,
 ) -> Result<(), crate::FrameworkError<'a, U, E>> {
     if cmd.owners_only && !ctx.framework().options().owners.contains(&ctx.author().id) {
         return Err(crate::FrameworkError::NotAnOwner { ctx });
@@ -88,6 +180,10 @@ pub async fn check_permissions_and_cooldown<'a, U, E>(
         }
     }
 
+    if cmd.requires_voice && ctx.author_voice_channel().is_none() {
+        return Err(crate::FrameworkError::NotInVoiceChannel { ctx });
+    }
+
     // Before running any pre-command checks, make sure the bot has the permissions it needs
     let bot_user_id = ctx.discord().cache.current_user_id();
     match missing_permissions(ctx, bot_user_id, cmd.required_bot_permissions).await {
@@ -123,15 +219,31 @@ pub async fn check_permissions_and_cooldown<'a, U, E>(
         }
     }
 
-    let cooldowns = &cmd.cooldowns;
-    let remaining_cooldown = cooldowns.lock().unwrap().remaining_cooldown(ctx);
-    if let Some(remaining_cooldown) = remaining_cooldown {
-        return Err(crate::FrameworkError::CooldownHit {
-            ctx,
-            remaining_cooldown,
-        });
+    if let Some(cooldown_exemption) = ctx.framework().options().cooldown_exemption {
+        if cooldown_exemption(ctx).await {
+            tracing::debug!("invoker is exempt from cooldowns");
+            return Ok(());
+        }
+    }
+
+    match cmd.cooldowns.try_start_cooldown(ctx).await {
+        Ok(true) => ctx.framework().started_cooldowns.insert(ctx.id()),
+        Ok(false) => {}
+        Err(exceeded) => {
+            tracing::debug!(
+                bucket = ?exceeded.bucket,
+                remaining_cooldown_ms = exceeded.remaining_cooldown.as_millis() as u64,
+                "cooldown hit"
+            );
+            return Err(crate::FrameworkError::CooldownHit {
+                ctx,
+                remaining_cooldown: exceeded.remaining_cooldown,
+                bucket: exceeded.bucket,
+                remaining_uses: exceeded.remaining_uses,
+                capacity: exceeded.capacity,
+            });
+        }
     }
-    cooldowns.lock().unwrap().start_cooldown(ctx);
 
     Ok(())
 }
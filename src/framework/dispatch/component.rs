@@ -0,0 +1,83 @@
+//! Dispatches message component interactions onto [`crate::ComponentHandler`]s
+
+use crate::serenity_prelude as serenity;
+use tracing::Instrument as _;
+
+/// Finds the component handler matching the interaction's `custom_id`, runs its check and then
+/// its action. Interactions without a matching handler are ignored, since they're probably
+/// handled by a collector
+pub async fn dispatch_component_interaction<'a, U, E>(
+    framework: &'a crate::Framework<U, E>,
+    ctx: &'a serenity::Context,
+    interaction: &'a serenity::MessageComponentInteraction,
+    has_sent_initial_response: &'a std::sync::atomic::AtomicBool,
+    has_sent_new_message: &'a std::sync::atomic::AtomicBool,
+) -> Result<(), crate::FrameworkError<'a, U, E>> {
+    let handler = match crate::find_component_handler(
+        &framework.options.component_handlers,
+        &interaction.data.custom_id,
+    ) {
+        Some(x) => x,
+        None => return Ok(()),
+    };
+
+    let span = super::common::command_span(
+        "component",
+        interaction.guild_id,
+        interaction.channel_id,
+        interaction.user.id,
+    );
+    span.record("command", handler.custom_id_prefix);
+    let start = std::time::Instant::now();
+    let result = run_component_handler(
+        framework,
+        ctx,
+        interaction,
+        has_sent_initial_response,
+        has_sent_new_message,
+        handler,
+    )
+    .instrument(span.clone())
+    .await;
+    span.in_scope(|| super::common::log_component_outcome(start, &result));
+    result
+}
+
+/// Runs the check and action of the given handler, see [`dispatch_component_interaction`]
+async fn run_component_handler<'a, U, E>(
+    framework: &'a crate::Framework<U, E>,
+    ctx: &'a serenity::Context,
+    interaction: &'a serenity::MessageComponentInteraction,
+    has_sent_initial_response: &'a std::sync::atomic::AtomicBool,
+    has_sent_new_message: &'a std::sync::atomic::AtomicBool,
+    handler: &'a crate::ComponentHandler<U, E>,
+) -> Result<(), crate::FrameworkError<'a, U, E>> {
+    let ctx = crate::ComponentContext {
+        discord: ctx,
+        interaction,
+        has_sent_initial_response,
+        has_sent_new_message,
+        framework,
+        handler,
+        data: framework.user_data().await,
+    };
+
+    if let Some(check) = handler.check {
+        match check(ctx).await {
+            Ok(true) => {}
+            Ok(false) => {
+                return Err(crate::FrameworkError::ComponentCheckFailed { error: None, ctx })
+            }
+            Err(error) => {
+                return Err(crate::FrameworkError::ComponentCheckFailed {
+                    error: Some(error),
+                    ctx,
+                })
+            }
+        }
+    }
+
+    (handler.action)(ctx)
+        .await
+        .map_err(|error| crate::FrameworkError::ComponentHandler { error, ctx })
+}
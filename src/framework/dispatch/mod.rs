@@ -1,12 +1,53 @@
 //! Contains all code to dispatch incoming events onto framework commands
 
 mod common;
+mod component;
 mod prefix;
 mod slash;
 
 pub use prefix::{dispatch_message, find_command};
 
 use crate::serenity_prelude as serenity;
+use tracing::Instrument as _;
+
+/// Registers all application commands in the given scope in a background task, forwarding errors
+/// to the error handler.
+///
+/// Running it in the background keeps the Ready event, and with it the user's event listener,
+/// from waiting for a round of requests per guild
+fn register_commands<U, E>(
+    framework: &crate::Framework<U, E>,
+    ctx: &serenity::Context,
+    scope: crate::CommandRegistrationScope,
+) where
+    U: 'static,
+    E: 'static,
+{
+    let guild_ids = match scope {
+        crate::CommandRegistrationScope::Global => vec![None],
+        crate::CommandRegistrationScope::Guilds(guild_ids) => {
+            guild_ids.into_iter().map(Some).collect::<Vec<_>>()
+        }
+    };
+    let commands = crate::builtins::application_command_payloads(&framework.options.commands);
+    let on_error = framework.options.on_error;
+    let http = ctx.http.clone();
+
+    tokio::spawn(async move {
+        for guild_id in guild_ids {
+            if let Err(error) = crate::builtins::sync_application_command_payloads(
+                &http,
+                guild_id,
+                commands.clone(),
+                false,
+            )
+            .await
+            {
+                on_error(crate::FrameworkError::CommandRegistration { error, guild_id }).await;
+            }
+        }
+    });
+}
 
 /// Central event handling function of this library
 pub async fn dispatch_event<U, E>(
@@ -14,7 +55,21 @@ pub async fn dispatch_event<U, E>(
     ctx: serenity::Context,
     event: &crate::Event<'_>,
 ) where
-    U: Send + Sync,
+    U: Send + Sync + 'static,
+    E: 'static,
+{
+    let span = tracing::debug_span!("dispatch", event = event.name());
+    handle_event(framework, ctx, event).instrument(span).await
+}
+
+/// Runs the framework's handling of an event and the user's event listener, see [`dispatch_event`]
+async fn handle_event<U, E>(
+    framework: &crate::Framework<U, E>,
+    ctx: serenity::Context,
+    event: &crate::Event<'_>,
+) where
+    U: Send + Sync + 'static,
+    E: 'static,
 {
     match event {
         crate::Event::Ready { data_about_bot } => {
@@ -32,6 +87,31 @@ pub async fn dispatch_event<U, E>(
                 // discarding duplicate Discord bot ready event
                 // (happens regularly when bot is online for long period of time)
             }
+
+            let command_registration =
+                Option::take(&mut *framework.command_registration.lock().unwrap());
+            if let Some(scope) = command_registration {
+                register_commands(framework, &ctx, scope);
+            }
+        }
+        #[cfg(feature = "voice")]
+        crate::Event::GuildCreate { guild, .. } => {
+            if let Some(timeout) = framework.options.voice_auto_leave {
+                framework.voice_auto_leave.update(&ctx, guild.id, timeout);
+            }
+        }
+        #[cfg(feature = "voice")]
+        crate::Event::GuildDelete { incomplete, .. } => {
+            framework.voice_auto_leave.remove_guild(incomplete.id);
+            framework.music_queues.remove_guild(incomplete.id);
+        }
+        #[cfg(feature = "voice")]
+        crate::Event::VoiceStateUpdate { new, .. } => {
+            if let (Some(timeout), Some(guild_id)) =
+                (framework.options.voice_auto_leave, new.guild_id)
+            {
+                framework.voice_auto_leave.update(&ctx, guild_id, timeout);
+            }
         }
         crate::Event::Message { new_message } => {
             if let Err(Some((error, command))) =
@@ -57,6 +137,9 @@ pub async fn dispatch_event<U, E>(
                 }
             }
         }
+        // Interactions that a command is waiting for, like a modal submission
+        crate::Event::InteractionCreate { interaction }
+            if framework.interaction_waiters.deliver(interaction) => {}
         crate::Event::InteractionCreate {
             interaction: serenity::Interaction::ApplicationCommand(interaction),
         } => {
@@ -65,6 +148,7 @@ pub async fn dispatch_event<U, E>(
                 &ctx,
                 interaction,
                 &std::sync::atomic::AtomicBool::new(false),
+                &std::sync::Mutex::new(None),
             )
             .await
             {
@@ -79,12 +163,28 @@ pub async fn dispatch_event<U, E>(
                 &ctx,
                 interaction,
                 &std::sync::atomic::AtomicBool::new(false),
+                &std::sync::Mutex::new(None),
             )
             .await
             {
                 command.on_error.unwrap_or(framework.options.on_error)(error).await;
             }
         }
+        crate::Event::InteractionCreate {
+            interaction: serenity::Interaction::MessageComponent(interaction),
+        } => {
+            if let Err(error) = component::dispatch_component_interaction(
+                framework,
+                &ctx,
+                interaction,
+                &std::sync::atomic::AtomicBool::new(false),
+                &std::sync::atomic::AtomicBool::new(false),
+            )
+            .await
+            {
+                (framework.options.on_error)(error).await;
+            }
+        }
         _ => {}
     }
 
@@ -1,6 +1,7 @@
 //! Dispatches incoming messages and message edits onto framework commands
 
 use crate::serenity_prelude as serenity;
+use tracing::Instrument as _;
 
 /// Checks if this message is a bot invocation by attempting to strip the prefix
 ///
@@ -145,8 +146,121 @@ where
     None
 }
 
+/// Finds commands whose name or alias is similar to the given (unknown) command name, for "did you
+/// mean" suggestions. Subcommands are considered as well. Returns the qualified names of the
+/// matches, most similar first
+fn similar_commands<U, E>(
+    commands: &[crate::Command<U, E>],
+    command_name: &str,
+    case_insensitive: bool,
+) -> Vec<String> {
+    /// Collects the edit distance of each command and its subcommands, recursively
+    fn collect<'a, U, E>(
+        commands: &'a [crate::Command<U, E>],
+        command_name: &str,
+        case_insensitive: bool,
+        matches: &mut Vec<(usize, &'a str)>,
+    ) {
+        for command in commands {
+            collect(
+                &command.subcommands,
+                command_name,
+                case_insensitive,
+                matches,
+            );
+            if command.hide_in_help || command.prefix_action.is_none() {
+                continue;
+            }
+
+            let distance = std::iter::once(command.name)
+                .chain(command.aliases.iter().copied())
+                .map(|name| match case_insensitive {
+                    true => crate::util::edit_distance(
+                        &name.to_lowercase(),
+                        &command_name.to_lowercase(),
+                    ),
+                    false => crate::util::edit_distance(name, command_name),
+                })
+                .min()
+                .expect("iterator contains at least the command name");
+            matches.push((distance, &command.qualified_name));
+        }
+    }
+
+    // Allow roughly one typo per three characters
+    let max_distance = std::cmp::max(1, command_name.chars().count() / 3);
+
+    let mut matches = Vec::new();
+    collect(commands, command_name, case_insensitive, &mut matches);
+    matches.retain(|&(distance, _)| distance <= max_distance);
+    matches.sort_by_key(|&(distance, _)| distance);
+    matches
+        .into_iter()
+        .take(3)
+        .map(|(_, qualified_name)| qualified_name.to_owned())
+        .collect()
+}
+
+/// Runs the given command with the given arguments, after checking permissions and cooldowns
+async fn run_invocation<'a, U, E>(
+    ctx: crate::PrefixContext<'a, U, E>,
+    args: &'a str,
+) -> Result<(), Option<(crate::FrameworkError<'a, U, E>, &'a crate::Command<U, E>)>>
+where
+    U: Send + Sync,
+{
+    let span = super::common::command_span(
+        "prefix",
+        ctx.msg.guild_id,
+        ctx.msg.channel_id,
+        ctx.msg.author.id,
+    );
+    span.record("command", ctx.command.qualified_name.as_str());
+    let start = std::time::Instant::now();
+    let result = execute_invocation(ctx, args).instrument(span.clone()).await;
+    span.in_scope(|| super::common::log_invocation_outcome(start, &result));
+    result
+}
+
+/// Runs checks and executes the invoked command, see [`run_invocation`]
+async fn execute_invocation<'a, U, E>(
+    ctx: crate::PrefixContext<'a, U, E>,
+    args: &'a str,
+) -> Result<(), Option<(crate::FrameworkError<'a, U, E>, &'a crate::Command<U, E>)>>
+where
+    U: Send + Sync,
+{
+    let command = ctx.command;
+    let action = command.prefix_action.ok_or(None)?;
+
+    super::common::check_permissions_and_cooldown(ctx.into(), command)
+        .await
+        .map_err(|e| Some((e, command)))?;
+
+    // Typing is broadcasted as long as this object is alive
+    let _typing_broadcaster = if command.broadcast_typing {
+        ctx.msg.channel_id.start_typing(&ctx.discord.http).ok()
+    } else {
+        None
+    };
+
+    (ctx.framework.options.pre_command)(crate::Context::Prefix(ctx)).await;
+
+    // Execute command
+    let res = (action)(ctx, args).await.map_err(|e| Some((e, command)));
+
+    (ctx.framework.options.post_command)(crate::Context::Prefix(ctx)).await;
+
+    res
+}
+
 /// Manually dispatches a message with the prefix framework.
 ///
+/// If [`crate::PrefixFrameworkOptions::help_command`] is set, the command of that name is invoked
+/// for messages consisting of just a prefix or bot mention (if
+/// [`crate::PrefixFrameworkOptions::help_when_mentioned`] is enabled) and for command groups
+/// invoked without a valid subcommand.
+///
 /// Returns:
 /// - Ok(()) if a command was successfully dispatched and run
 /// - Err(None) if no command was dispatched, for example if the message didn't contain a command or
@@ -173,13 +287,71 @@ where
         return Err(None);
     }
 
-    let (command, invoked_command_name, args) = find_command(
-        &framework.options.commands,
-        msg_content,
-        framework.options.prefix_options.case_insensitive_commands,
-    )
-    .ok_or(None)?;
-    let action = command.prefix_action.ok_or(None)?;
+    let data = framework.user_data().await;
+    let case_insensitive = framework.options.prefix_options.case_insensitive_commands;
+    let help_command = framework
+        .options
+        .prefix_options
+        .help_command
+        .as_deref()
+        .and_then(|name| find_command(&framework.options.commands, name, case_insensitive))
+        .map(|(help_command, _, _)| help_command);
+    // Creates a context for invoking the help command in place of the user's invocation
+    let help_context = move |help_command: &'a crate::Command<U, E>| crate::PrefixContext {
+        discord: ctx,
+        msg,
+        prefix,
+        invoked_command_name: help_command.name,
+        framework,
+        data,
+        command: help_command,
+    };
+
+    // A bare mention or prefix without any command invokes the help command
+    if msg_content.trim_end().is_empty() {
+        return match help_command {
+            Some(help_command)
+                if framework.options.prefix_options.help_when_mentioned && !triggered_by_edit =>
+            {
+                run_invocation(help_context(help_command), "").await
+            }
+            _ => Err(None),
+        };
+    }
+
+    let (command, invoked_command_name, args) =
+        match find_command(&framework.options.commands, msg_content, case_insensitive) {
+            Some(x) => x,
+            None => {
+                let command_name = msg_content.split_whitespace().next().unwrap_or("");
+                if framework.options.prefix_options.suggest_similar_commands
+                    && !command_name.is_empty()
+                    && !triggered_by_edit
+                {
+                    let suggestions = similar_commands(
+                        &framework.options.commands,
+                        command_name,
+                        case_insensitive,
+                    );
+                    (framework.options.on_error)(crate::FrameworkError::UnknownCommand {
+                        msg,
+                        prefix,
+                        msg_content,
+                        suggestions,
+                        ctx: crate::PartialContext {
+                            guild_id: msg.guild_id,
+                            channel_id: msg.channel_id,
+                            author: &msg.author,
+                            discord: ctx,
+                            framework,
+                            data,
+                        },
+                    })
+                    .await;
+                }
+                return Err(None);
+            }
+        };
 
     // Check if we should disregard this invocation if it was triggered by an edit
     let should_execute_if_triggered_by_edit = command.invoke_on_edit
@@ -188,33 +360,167 @@ where
         return Err(None);
     }
 
+    // Command groups which can't be invoked by themselves fall back to the help for that group
+    let group_help_command = match help_command {
+        Some(help_command) if !command.subcommands.is_empty() => Some(help_command),
+        _ => None,
+    };
+    if command.prefix_action.is_none() {
+        return match group_help_command {
+            Some(help_command) => {
+                run_invocation(help_context(help_command), &command.qualified_name).await
+            }
+            None => Err(None),
+        };
+    }
+
     let ctx = crate::PrefixContext {
         discord: ctx,
         msg,
         prefix,
         invoked_command_name,
         framework,
-        data: framework.user_data().await,
+        data,
         command,
     };
 
-    super::common::check_permissions_and_cooldown(ctx.into(), command)
-        .await
-        .map_err(|e| Some((e, command)))?;
+    let result = run_invocation(ctx, args).await;
+    let is_missing_subcommand = matches!(
+        &result,
+        Err(Some((crate::FrameworkError::ArgumentParse { input, .. }, _)))
+            if is_missing_subcommand(args, input.as_deref())
+    );
+    match group_help_command {
+        Some(help_command) if is_missing_subcommand => {
+            // The user didn't mean to invoke the group itself, so its cooldown shouldn't count
+            crate::Context::Prefix(ctx).refund_cooldown().await;
+            run_invocation(help_context(help_command), &command.qualified_name).await
+        }
+        _ => result,
+    }
+}
 
-    // Typing is broadcasted as long as this object is alive
-    let _typing_broadcaster = if command.broadcast_typing {
-        msg.channel_id.start_typing(&ctx.discord.http).ok()
-    } else {
-        None
-    };
+/// Returns whether the arguments of a command group failed to parse because the user gave no
+/// subcommand, or one that doesn't exist, rather than because of a mistake in the group's own
+/// arguments. `input` is the input on which parsing failed, if any
+fn is_missing_subcommand(args: &str, input: Option<&str>) -> bool {
+    // Any valid subcommand would have been resolved by `find_command` already, so the first
+    // token is not a subcommand
+    match args.split_whitespace().next() {
+        None => true,
+        Some(first_token) => input.map(str::trim) == Some(first_token),
+    }
+}
 
-    (framework.options.pre_command)(crate::Context::Prefix(ctx)).await;
+#[test]
+fn test_similar_commands() {
+    let commands = vec![
+        crate::Command::<(), ()> {
+            name: "help",
+            qualified_name: "help".into(),
+            prefix_action: Some(|_, _| Box::pin(async { Ok(()) })),
+            ..Default::default()
+        },
+        crate::Command {
+            name: "config",
+            qualified_name: "config".into(),
+            subcommands: vec![crate::Command {
+                name: "set",
+                qualified_name: "config set".into(),
+                aliases: &["put"],
+                prefix_action: Some(|_, _| Box::pin(async { Ok(()) })),
+                ..Default::default()
+            }],
+            ..Default::default()
+        },
+    ];
 
-    // Execute command
-    let res = (action)(ctx, args).await.map_err(|e| Some((e, command)));
+    assert_eq!(similar_commands(&commands, "hlep", true), vec!["help"]);
+    assert_eq!(similar_commands(&commands, "HELP", true), vec!["help"]);
+    assert!(similar_commands(&commands, "HELPP", false).is_empty());
+    assert_eq!(similar_commands(&commands, "pu", true), vec!["config set"]);
+    assert!(similar_commands(&commands, "xyz", true).is_empty());
+}
 
-    (framework.options.post_command)(crate::Context::Prefix(ctx)).await;
+#[cfg(test)]
+#[tokio::test]
+async fn test_group_help_fallback() {
+    // Takes the arguments "a <number>"
+    let tag = crate::Command::<(), String> {
+        name: "tag",
+        qualified_name: "tag".into(),
+        cooldowns: crate::Cooldowns::new(crate::CooldownConfig {
+            user: Some(std::time::Duration::from_secs(60)),
+            user_uses: Some(2),
+            ..Default::default()
+        }),
+        subcommands: vec![crate::Command {
+            name: "show",
+            qualified_name: "tag show".into(),
+            ..Default::default()
+        }],
+        prefix_action: Some(|ctx, args| {
+            Box::pin(async move {
+                let mut tokens = args.split_whitespace();
+                let invalid = match (tokens.next(), tokens.next()) {
+                    (Some("a"), Some(number)) if number.parse::<u32>().is_ok() => None,
+                    (Some("a"), number) => Some(number),
+                    (first, _) => Some(first),
+                };
+                if let Some(input) = invalid {
+                    return Err(crate::FrameworkError::ArgumentParse {
+                        error: "invalid argument".into(),
+                        input: input.map(String::from),
+                        ctx: ctx.into(),
+                    });
+                }
+                let _ = crate::say_reply(ctx.into(), "tag").await;
+                Ok(())
+            })
+        }),
+        ..Default::default()
+    };
+    let help = crate::Command::<(), String> {
+        name: "help",
+        qualified_name: "help".into(),
+        prefix_action: Some(|ctx, args| {
+            Box::pin(async move {
+                let _ = crate::say_reply(ctx.into(), format!("help: {}", args)).await;
+                Ok(())
+            })
+        }),
+        ..Default::default()
+    };
+    let harness = crate::testing::TestHarness::new(
+        crate::FrameworkOptions {
+            commands: vec![tag, help],
+            prefix_options: crate::PrefixFrameworkOptions {
+                prefix: Some("~".into()),
+                help_command: Some("help".into()),
+                ..Default::default()
+            },
+            on_error: |_| Box::pin(async {}),
+            ..Default::default()
+        },
+        (),
+    );
+    let reply = |content: &'static str| {
+        let harness = &harness;
+        async move {
+            harness.send_message(content).await;
+            harness
+                .take_replies()
+                .into_iter()
+                .filter_map(|reply| reply.content)
+                .collect::<Vec<_>>()
+        }
+    };
 
-    res
+    // No subcommand, or one that doesn't exist
+    assert_eq!(reply("~tag").await, ["help: tag"]);
+    assert_eq!(reply("~tag shwo").await, ["help: tag"]);
+    // The help fallbacks didn't use up the group's cooldown
+    assert_eq!(reply("~tag a 1").await, ["tag"]);
+    // A mistake in the group's own arguments is a real error
+    assert!(reply("~tag a x").await.is_empty());
 }
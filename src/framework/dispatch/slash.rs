@@ -1,6 +1,7 @@
 //! Dispatches interactions onto framework commands
 
 use crate::serenity_prelude as serenity;
+use tracing::Instrument as _;
 
 /// Check if the interaction with the given name and arguments matches any framework command
 fn find_matching_command<'a, 'b, U, E>(
@@ -38,6 +39,7 @@ pub async fn extract_command_and_run_checks<'a, U, E>(
     ctx: &'a serenity::Context,
     interaction: crate::ApplicationCommandOrAutocompleteInteraction<'a>,
     has_sent_initial_response: &'a std::sync::atomic::AtomicBool,
+    last_followup_id: &'a std::sync::Mutex<Option<serenity::MessageId>>,
 ) -> Result<
     (
         crate::ApplicationContext<'a, U, E>,
@@ -51,12 +53,10 @@ pub async fn extract_command_and_run_checks<'a, U, E>(
         &framework.options.commands,
     );
     let (command, leaf_interaction_options) = search_result.ok_or_else(|| {
-        println!(
-            "Warning: received unknown interaction \"{}\"",
-            interaction.data().name
-        );
+        tracing::warn!(name = %interaction.data().name, "received unknown interaction");
         None
     })?;
+    tracing::Span::current().record("command", command.qualified_name.as_str());
 
     let ctx = crate::ApplicationContext {
         data: framework.user_data().await,
@@ -65,6 +65,7 @@ pub async fn extract_command_and_run_checks<'a, U, E>(
         interaction,
         command,
         has_sent_initial_response,
+        last_followup_id,
     };
 
     super::common::check_permissions_and_cooldown(ctx.into(), command)
@@ -74,19 +75,72 @@ pub async fn extract_command_and_run_checks<'a, U, E>(
     Ok((ctx, leaf_interaction_options))
 }
 
+/// Runs the command action. If it doesn't respond within
+/// [`crate::FrameworkOptions::auto_defer_threshold`], the response is deferred while the action
+/// keeps running
+async fn run_with_auto_defer<'a, U, E>(
+    ctx: crate::ApplicationContext<'a, U, E>,
+    action: crate::BoxFuture<'a, Result<(), crate::FrameworkError<'a, U, E>>>,
+) -> Result<(), crate::FrameworkError<'a, U, E>> {
+    let threshold = Box::pin(tokio::time::sleep(
+        ctx.framework.options.auto_defer_threshold,
+    ));
+    match futures_util::future::select(action, threshold).await {
+        futures_util::future::Either::Left((action_result, _)) => action_result,
+        futures_util::future::Either::Right(((), action)) => {
+            // Only defers if the action hasn't claimed the initial response yet, even if its
+            // request is still in flight
+            if let Err(e) = ctx.defer_response(ctx.command.ephemeral).await {
+                tracing::warn!(error = %e, "couldn't automatically defer response");
+            }
+            action.await
+        }
+    }
+}
+
 /// Dispatches this interaction onto framework commands, i.e. runs the associated command
 pub async fn dispatch_interaction<'a, U, E>(
     framework: &'a crate::Framework<U, E>,
     ctx: &'a serenity::Context,
     interaction: &'a serenity::ApplicationCommandInteraction,
-    // Need to pass this in from outside because of lifetime issues
+    // Need to pass these in from outside because of lifetime issues
+    has_sent_initial_response: &'a std::sync::atomic::AtomicBool,
+    last_followup_id: &'a std::sync::Mutex<Option<serenity::MessageId>>,
+) -> Result<(), Option<(crate::FrameworkError<'a, U, E>, &'a crate::Command<U, E>)>> {
+    let span = super::common::command_span(
+        "application",
+        interaction.guild_id,
+        interaction.channel_id,
+        interaction.user.id,
+    );
+    let start = std::time::Instant::now();
+    let result = run_interaction(
+        framework,
+        ctx,
+        interaction,
+        has_sent_initial_response,
+        last_followup_id,
+    )
+    .instrument(span.clone())
+    .await;
+    span.in_scope(|| super::common::log_invocation_outcome(start, &result));
+    result
+}
+
+/// Runs the command associated with this interaction, see [`dispatch_interaction`]
+async fn run_interaction<'a, U, E>(
+    framework: &'a crate::Framework<U, E>,
+    ctx: &'a serenity::Context,
+    interaction: &'a serenity::ApplicationCommandInteraction,
     has_sent_initial_response: &'a std::sync::atomic::AtomicBool,
+    last_followup_id: &'a std::sync::Mutex<Option<serenity::MessageId>>,
 ) -> Result<(), Option<(crate::FrameworkError<'a, U, E>, &'a crate::Command<U, E>)>> {
     let (ctx, options) = extract_command_and_run_checks(
         framework,
         ctx,
         crate::ApplicationCommandOrAutocompleteInteraction::ApplicationCommand(interaction),
         has_sent_initial_response,
+        last_followup_id,
     )
     .await?;
 
@@ -102,20 +156,20 @@ pub async fn dispatch_interaction<'a, U, E>(
         },
         ctx.command,
     ));
-    let action_result = match interaction.data.kind {
+    let action = match interaction.data.kind {
         serenity::ApplicationCommandType::ChatInput => {
             let action = ctx
                 .command
                 .slash_action
                 .ok_or(command_structure_mismatch_error)?;
-            action(ctx, options).await
+            action(ctx, options)
         }
         serenity::ApplicationCommandType::User => {
             match (ctx.command.context_menu_action, &interaction.data.target) {
                 (
                     Some(crate::ContextMenuCommandAction::User(action)),
                     Some(serenity::ResolvedTarget::User(user, _)),
-                ) => action(ctx, user.clone()).await,
+                ) => action(ctx, user.clone()),
                 _ => return Err(command_structure_mismatch_error),
             }
         }
@@ -124,12 +178,21 @@ pub async fn dispatch_interaction<'a, U, E>(
                 (
                     Some(crate::ContextMenuCommandAction::Message(action)),
                     Some(serenity::ResolvedTarget::Message(message)),
-                ) => action(ctx, message.clone()).await,
+                ) => action(ctx, message.clone()),
                 _ => return Err(command_structure_mismatch_error),
             }
         }
         _ => return Err(None),
     };
+    let auto_defer = ctx
+        .command
+        .auto_defer
+        .unwrap_or(framework.options.auto_defer);
+    let action_result = if auto_defer {
+        run_with_auto_defer(ctx, action).await
+    } else {
+        action.await
+    };
 
     (framework.options.post_command)(crate::Context::Application(ctx)).await;
 
@@ -142,14 +205,16 @@ pub async fn dispatch_autocomplete<'a, U, E>(
     framework: &'a crate::Framework<U, E>,
     ctx: &'a serenity::Context,
     interaction: &'a serenity::AutocompleteInteraction,
-    // Need to pass this in from outside because of lifetime issues
+    // Need to pass these in from outside because of lifetime issues
     has_sent_initial_response: &'a std::sync::atomic::AtomicBool,
+    last_followup_id: &'a std::sync::Mutex<Option<serenity::MessageId>>,
 ) -> Result<(), Option<(crate::FrameworkError<'a, U, E>, &'a crate::Command<U, E>)>> {
     let (ctx, options) = extract_command_and_run_checks(
         framework,
         ctx,
         crate::ApplicationCommandOrAutocompleteInteraction::Autocomplete(interaction),
         has_sent_initial_response,
+        last_followup_id,
     )
     .await?;
 
@@ -170,7 +235,7 @@ pub async fn dispatch_autocomplete<'a, U, E>(
         let autocomplete_response = match autocomplete_callback(ctx, focused_option_json).await {
             Ok(x) => x,
             Err(e) => {
-                println!("Warning: couldn't generate autocomplete response: {}", e);
+                tracing::warn!(error = %e, "couldn't generate autocomplete response");
                 return Err(None);
             }
         };
@@ -183,7 +248,7 @@ pub async fn dispatch_autocomplete<'a, U, E>(
             })
             .await
         {
-            println!("Warning: couldn't send autocomplete response: {}", e);
+            tracing::warn!(error = %e, "couldn't send autocomplete response");
         }
     }
 
@@ -1,10 +1,12 @@
 //! The central Framework struct that ties everything together.
 
-mod dispatch;
+pub(crate) mod dispatch;
 
 mod builder;
 pub use builder::*;
 
+mod waiter;
+
 use crate::{serenity_prelude as serenity, BoxFuture};
 
 pub use dispatch::{dispatch_message, find_command};
@@ -18,8 +20,26 @@ pub struct Framework<U, E> {
 
     /// Will be initialized to Some on construction, and then taken out on startup
     client: std::sync::Mutex<Option<serenity::Client>>,
-    /// Initialized to Some during construction; so shouldn't be None at any observable point
-    shard_manager: std::sync::Arc<tokio::sync::Mutex<serenity::ShardManager>>,
+    /// Initialized to Some during construction, unless this framework was created offline by
+    /// `TestHarness`
+    shard_manager: Option<std::sync::Arc<tokio::sync::Mutex<serenity::ShardManager>>>,
+    /// Where all responses are sent. Sends to Discord, unless this framework was created offline
+    /// by `TestHarness`
+    response_sink: std::sync::Arc<dyn crate::ResponseSink>,
+    /// Invocations whose cooldown can be refunded
+    started_cooldowns: crate::StartedCooldowns,
+    /// Commands waiting for a modal submission or a component interaction
+    interaction_waiters: waiter::InteractionWaiters,
+    /// Leaves voice channels in which the bot is alone, see [`crate::FrameworkOptions::voice_auto_leave`]
+    #[cfg(feature = "voice")]
+    voice_auto_leave: crate::VoiceAutoLeave,
+    /// Music queues of all guilds, and the channel which their track notifications are sent
+    /// through
+    #[cfg(feature = "voice")]
+    music_queues: crate::MusicQueues,
+    /// Set by [`FrameworkBuilder::register_commands`]. Taken out and executed on first Ready
+    /// gateway event
+    command_registration: std::sync::Mutex<Option<CommandRegistrationScope>>,
     /// Filled with Some on construction. Taken out and executed on first Ready gateway event
     user_data_setup: std::sync::Mutex<
         Option<
@@ -52,6 +72,9 @@ impl<U, E> Framework<U, E> {
     /// user ID or connected guilds can be made available to the user data setup function. The user
     /// data setup is not allowed to return Result because there would be no reasonable
     /// course of action on error.
+    ///
+    /// With the `voice` feature enabled, [songbird](https://docs.rs/songbird) is registered on the
+    /// client as well.
     pub async fn new<F>(
         client_builder: serenity::ClientBuilder,
         user_data_setup: F,
@@ -69,10 +92,8 @@ impl<U, E> Framework<U, E> {
         U: Send + Sync + 'static,
         E: Send + 'static,
     {
-
-        use songbird::register;
-
-        let client_builder = register(client_builder);
+        #[cfg(feature = "voice")]
+        let client_builder = songbird::register(client_builder);
 
         // let self_1 = std::sync::Arc::new(Self {
         //     user_data: once_cell::sync::OnceCell::new(),
@@ -88,17 +109,7 @@ impl<U, E> Framework<U, E> {
         // let self_2 = self_1.clone();
         use std::sync::{Arc, Mutex};
 
-        /// Fill in [`Command::qualified_name`] with the correct values
-        fn set_qualified_names<U, E>(command: &mut crate::Command<U, E>) {
-            for subcommand in &mut command.subcommands {
-                subcommand.qualified_name =
-                    format!("{} {}", command.qualified_name, subcommand.name);
-                set_qualified_names(subcommand);
-            }
-        }
-        for command in &mut options.commands {
-            set_qualified_names(command);
-        }
+        set_qualified_names(&mut options.commands);
 
         let framework_cell = Arc::new(once_cell::sync::OnceCell::<Arc<Self>>::new());
         let framework_cell_2 = framework_cell.clone();
@@ -116,13 +127,52 @@ impl<U, E> Framework<U, E> {
             user_data: once_cell::sync::OnceCell::new(),
             user_data_setup: Mutex::new(Some(Box::new(user_data_setup))),
             options,
-            shard_manager: client.shard_manager.clone(),
+            shard_manager: Some(client.shard_manager.clone()),
             client: Mutex::new(Some(client)),
+            response_sink: Arc::new(crate::HttpSink),
+            interaction_waiters: Default::default(),
+            started_cooldowns: Default::default(),
+            command_registration: Mutex::new(None),
+            #[cfg(feature = "voice")]
+            voice_auto_leave: Default::default(),
+            #[cfg(feature = "voice")]
+            music_queues: Default::default(),
         });
         let _: Result<_, _> = framework_cell.set(framework.clone());
         Ok(framework)
     }
 
+    /// Creates a framework which is not connected to Discord, with the user data already set.
+    ///
+    /// All responses go to the given sink instead of Discord. Used by
+    /// [`crate::testing::TestHarness`]
+    #[cfg(any(test, feature = "testing"))]
+    pub(crate) fn new_offline(
+        mut options: crate::FrameworkOptions<U, E>,
+        user_data: U,
+        response_sink: std::sync::Arc<dyn crate::ResponseSink>,
+    ) -> Self {
+        set_qualified_names(&mut options.commands);
+
+        let user_data_cell = once_cell::sync::OnceCell::new();
+        let _: Result<_, _> = user_data_cell.set(user_data);
+        Self {
+            user_data: user_data_cell,
+            user_data_setup: std::sync::Mutex::new(None),
+            options,
+            shard_manager: None,
+            client: std::sync::Mutex::new(None),
+            response_sink,
+            interaction_waiters: Default::default(),
+            started_cooldowns: Default::default(),
+            command_registration: std::sync::Mutex::new(None),
+            #[cfg(feature = "voice")]
+            voice_auto_leave: Default::default(),
+            #[cfg(feature = "voice")]
+            music_queues: Default::default(),
+        }
+    }
+
     /// Small utility function for starting the framework that is agnostic over client sharding
     async fn start_with<F: std::future::Future<Output = serenity::Result<()>>>(
         self: std::sync::Arc<Self>,
@@ -139,11 +189,37 @@ impl<U, E> Framework<U, E> {
             .take()
             .expect("Prepared client is missing");
 
+        // Dispatch the events emitted by the framework itself, like music queue notifications
+        #[cfg(feature = "voice")]
+        let framework_event_task = {
+            let framework = self.clone();
+            let mut receiver = self.music_queues.take_event_receiver();
+            tokio::spawn(async move {
+                if let Some(receiver) = &mut receiver {
+                    while let Some(message) = receiver.recv().await {
+                        match message {
+                            crate::QueueMessage::Event(ctx, event) => {
+                                dispatch::dispatch_event(&*framework, ctx, &event).await;
+                            }
+                            crate::QueueMessage::Requeue(queue, track) => {
+                                queue.requeue(&track).await;
+                            }
+                            crate::QueueMessage::Disconnected(queue) => {
+                                framework.music_queues.remove(&queue);
+                            }
+                        }
+                    }
+                }
+            })
+        };
+
         let edit_track_cache_purge_task = tokio::spawn(async move {
             loop {
                 if let Some(edit_tracker) = &self.options.prefix_options.edit_tracker {
                     edit_tracker.write().unwrap().purge();
                 }
+                self.options.cooldown_store.purge().await;
+                self.started_cooldowns.purge();
                 // not sure if the purging interval should be configurable
                 tokio::time::sleep(std::time::Duration::from_secs(60)).await;
             }
@@ -153,6 +229,8 @@ impl<U, E> Framework<U, E> {
         start(client).await?;
 
         edit_track_cache_purge_task.abort();
+        #[cfg(feature = "voice")]
+        framework_event_task.abort();
 
         Ok(())
     }
@@ -183,10 +261,87 @@ impl<U, E> Framework<U, E> {
     }
 
     /// Returns the serenity's client shard manager.
+    ///
+    /// A framework created by `TestHarness` has no shard manager, since there is no Discord
+    /// connection. Use [`Self::try_shard_manager`] in code that runs under the harness
+    ///
+    /// # Panics
+    /// Panics if this framework was created by `TestHarness` (`testing` feature only)
     pub fn shard_manager(&self) -> std::sync::Arc<tokio::sync::Mutex<serenity::ShardManager>> {
+        match self.try_shard_manager() {
+            Some(shard_manager) => shard_manager,
+            None => panic!("framework created by TestHarness has no shard manager"),
+        }
+    }
+
+    /// Returns the serenity's client shard manager, or None if this framework was created offline
+    /// by `TestHarness`
+    pub fn try_shard_manager(
+        &self,
+    ) -> Option<std::sync::Arc<tokio::sync::Mutex<serenity::ShardManager>>> {
         self.shard_manager.clone()
     }
 
+    /// Sends or edits a message through this framework's response sink, applying
+    /// [`crate::FrameworkOptions::allowed_mentions`]
+    pub(crate) async fn send_message(
+        &self,
+        discord: &serenity::Context,
+        request: crate::MessageRequest<'_, '_>,
+    ) -> Result<serenity::Message, serenity::Error> {
+        self.response_sink
+            .send_message(discord, self.options.allowed_mentions.as_ref(), request)
+            .await
+    }
+
+    /// Responds to an interaction or deletes a response through this framework's response sink
+    pub(crate) async fn send_response(
+        &self,
+        discord: &serenity::Context,
+        request: crate::ResponseRequest<'_, '_>,
+    ) -> Result<(), serenity::Error> {
+        self.response_sink
+            .send(discord, self.options.allowed_mentions.as_ref(), request)
+            .await
+    }
+
+    /// Waits for the next modal submission or component interaction that matches the filter.
+    /// Returns None after the timeout.
+    ///
+    /// A matching interaction is not dispatched to [`crate::FrameworkOptions::component_handlers`]
+    pub(crate) async fn wait_for_interaction(
+        &self,
+        filter: impl Fn(&serenity::Interaction) -> bool + Send + Sync + 'static,
+        timeout: std::time::Duration,
+    ) -> Option<serenity::Interaction> {
+        self.interaction_waiters.wait(filter, timeout).await
+    }
+
+    /// If the cooldown of the given invocation was started and hasn't been refunded yet, returns
+    /// true and forgets about it. See [`crate::Context::refund_cooldown`]
+    pub(crate) fn take_started_cooldown(&self, invocation_id: u64) -> bool {
+        self.started_cooldowns.take(invocation_id)
+    }
+
+    /// Returns the music queue of the given guild, creating it on first use. See
+    /// [`crate::MusicQueue`]
+    #[cfg(feature = "voice")]
+    pub async fn music_queue(
+        &self,
+        discord: &serenity::Context,
+        guild_id: serenity::GuildId,
+    ) -> crate::MusicQueue {
+        let songbird = songbird::get(discord)
+            .await
+            .expect("songbird is registered in Framework::new");
+        self.music_queues.get_or_create(
+            discord,
+            songbird,
+            guild_id,
+            self.options.music_idle_timeout,
+        )
+    }
+
     /// Retrieves user data, or blocks until it has been initialized (once the Ready event has been
     /// received).
     pub async fn user_data(&self) -> &U {
@@ -198,3 +353,17 @@ impl<U, E> Framework<U, E> {
         }
     }
 }
+
+/// Fill in [`crate::Command::qualified_name`] with the correct values
+fn set_qualified_names<U, E>(commands: &mut [crate::Command<U, E>]) {
+    /// Fills in the qualified names of the given command's subcommands, recursively
+    fn set_subcommand_qualified_names<U, E>(command: &mut crate::Command<U, E>) {
+        for subcommand in &mut command.subcommands {
+            subcommand.qualified_name = format!("{} {}", command.qualified_name, subcommand.name);
+            set_subcommand_qualified_names(subcommand);
+        }
+    }
+    for command in commands {
+        set_subcommand_qualified_names(command);
+    }
+}
@@ -0,0 +1,59 @@
+//! Lets commands wait for follow-up interactions, like a modal submission or a click on a button of
+//! their response, through the framework's own event dispatch
+
+use crate::serenity_prelude as serenity;
+
+/// A pending [`InteractionWaiters::wait`] call
+struct InteractionWaiter {
+    /// Returns whether the interaction is the one being waited for
+    filter: Box<dyn Fn(&serenity::Interaction) -> bool + Send + Sync>,
+    /// Receives the matching interaction
+    sender: tokio::sync::oneshot::Sender<serenity::Interaction>,
+}
+
+/// All commands that are currently waiting for an interaction.
+///
+/// Unlike serenity's collectors, these are fed by the framework's event dispatch, so they also see
+/// the interactions dispatched by `TestHarness`
+#[derive(Default)]
+pub(crate) struct InteractionWaiters(std::sync::Mutex<Vec<InteractionWaiter>>);
+
+impl InteractionWaiters {
+    /// Waits for the next interaction that matches the filter. Returns None after the timeout
+    pub(crate) async fn wait(
+        &self,
+        filter: impl Fn(&serenity::Interaction) -> bool + Send + Sync + 'static,
+        timeout: std::time::Duration,
+    ) -> Option<serenity::Interaction> {
+        let (sender, receiver) = tokio::sync::oneshot::channel();
+        {
+            let mut waiters = self.0.lock().unwrap();
+            // Drop the waiters that have timed out
+            waiters.retain(|waiter| !waiter.sender.is_closed());
+            waiters.push(InteractionWaiter {
+                filter: Box::new(filter),
+                sender,
+            });
+        }
+
+        tokio::time::timeout(timeout, receiver).await.ok()?.ok()
+    }
+
+    /// Hands the interaction to the first waiter whose filter matches. Returns false if nobody was
+    /// waiting for it
+    pub(crate) fn deliver(&self, interaction: &serenity::Interaction) -> bool {
+        let mut waiters = self.0.lock().unwrap();
+        waiters.retain(|waiter| !waiter.sender.is_closed());
+        match waiters
+            .iter()
+            .position(|waiter| (waiter.filter)(interaction))
+        {
+            Some(i) => waiters
+                .swap_remove(i)
+                .sender
+                .send(interaction.clone())
+                .is_ok(),
+            None => false,
+        }
+    }
+}
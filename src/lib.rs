@@ -63,6 +63,10 @@ You can run the framework_usage example with
 ## Crate features
 
 - collector: enables serenity's `collector` feature
+- voice: registers [songbird](https://docs.rs/songbird) on the client and enables the voice
+  helpers on [`Context`], like `Context::join_author_channel`, as well as the guild-scoped
+  `MusicQueue`. Enables serenity's `voice` feature (enabled by default)
+- testing: enables the `testing` module, which runs commands against an offline framework
 
 # How to use
 
@@ -209,6 +213,17 @@ serenity::Member, serenity::UserId, serenity::ReactionType, serenity::GatewayInt
 # );
 ```
 
+## Testing commands
+The `testing` module (behind the `testing` feature) lets you run your commands without connecting to Discord: feed synthetic
+messages and interactions into the framework and assert on the responses it would have sent.
+
+## Logging
+poise reports what it's doing via [`tracing`](https://docs.rs/tracing). Every command invocation
+(and every component handler invocation, with `kind = "component"`) runs in a `command` span with
+the command name, guild, channel and user, and finishes with an
+event that records the outcome and latency. Install any tracing subscriber, for example
+`tracing_subscriber::fmt::init()`, to route these into your logs and filter them by level.
+
 # About the weird name
 I'm bad at names. Google lists "poise" as a synonym to "serenity" which is the Discord library
 underlying this framework, so that's what I chose.
@@ -235,9 +250,20 @@ pub use reply::*;
 mod cooldown;
 pub use cooldown::*;
 
+mod component;
+pub use component::*;
+
+mod modal;
+pub use modal::*;
+mod voice;
+pub use voice::*;
+
 pub(crate) mod util;
 
 pub mod builtins;
+#[cfg(any(test, feature = "testing"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+pub mod testing;
 /// See [`builtins`]
 #[deprecated = "`samples` module was renamed to `builtins`"]
 pub mod samples {
@@ -250,6 +276,8 @@ pub use futures_core;
 pub use futures_util;
 pub use poise_macros::*;
 pub use serenity;
+#[cfg(feature = "voice")]
+pub use songbird;
 
 /// This module re-exports a bunch of items from all over serenity. Useful if you can't
 /// remember the full paths of serenity items.
@@ -273,7 +301,9 @@ pub mod serenity_prelude {
         http::*,
         model::{
             event::*,
-            interactions::{application_command::*, autocomplete::*, message_component::*, *},
+            interactions::{
+                application_command::*, autocomplete::*, message_component::*, modal::*, *,
+            },
             prelude::*,
         },
         prelude::*,
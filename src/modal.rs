@@ -0,0 +1,217 @@
+//! Modal (form) support for application commands, see [`Modal`]
+
+use crate::serenity_prelude as serenity;
+
+/// A Discord modal: a form pop-up with text inputs, which can be sent as the initial response to
+/// an application command. Usually implemented with [`#[derive(Modal)]`](macro@crate::Modal), and
+/// executed with [`crate::ApplicationContext::execute_modal`]
+///
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// #[derive(Debug, poise::Modal)]
+/// #[name = "Report a bug"] // Title of the modal
+/// struct BugReport {
+///     #[name = "Summary"] // Label of the text input
+///     #[placeholder = "The bot crashes when..."]
+///     #[max_length = 100]
+///     summary: String,
+///     #[name = "Steps to reproduce"]
+///     #[paragraph] // Multi-line text input
+///     steps: Option<String>, // Optional text inputs may be left empty
+/// }
+///
+/// #[poise::command(slash_command)]
+/// async fn report(ctx: poise::Context<'_, (), Error>) -> Result<(), Error> {
+///     let ctx = match ctx {
+///         poise::Context::Application(ctx) => ctx,
+///         poise::Context::Prefix(_) => return Ok(()),
+///     };
+///
+///     let timeout = std::time::Duration::from_secs(600);
+///     match ctx.execute_modal::<BugReport>(Some(timeout)).await? {
+///         Some(report) => {
+///             poise::send_application_reply(ctx, |m| {
+///                 m.content(format!("Thanks for your report: {:?}", report))
+///             })
+///             .await?;
+///         }
+///         None => println!("User didn't submit the bug report in time"),
+///     }
+///     Ok(())
+/// }
+/// ```
+pub trait Modal: Sized {
+    /// Fills in the interaction response builder to open this modal, with the given `custom_id`
+    fn create(builder: &mut serenity::CreateInteractionResponse, custom_id: String);
+
+    /// Parses the values of a submitted modal into this type
+    fn parse(data: serenity::ModalSubmitInteractionData) -> Result<Self, &'static str>;
+}
+
+/// Takes the value of the text input with the given `custom_id` out of a submitted modal.
+/// Used by `#[derive(Modal)]`
+///
+/// Returns None if there's no such text input or if it was left empty
+#[doc(hidden)]
+pub fn _find_modal_text(
+    data: &mut serenity::ModalSubmitInteractionData,
+    custom_id: &str,
+) -> Option<String> {
+    data.components
+        .iter_mut()
+        .flat_map(|row| row.components.iter_mut())
+        .find_map(|component| match component {
+            serenity::ActionRowComponent::InputText(input) if input.custom_id == custom_id => {
+                Some(std::mem::take(&mut input.value))
+            }
+            _ => None,
+        })
+        .filter(|value| !value.is_empty())
+}
+
+/// How long [`crate::ApplicationContext::execute_modal`] waits for a submission if no timeout is
+/// given
+const DEFAULT_MODAL_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3600);
+
+impl<U, E> crate::ApplicationContext<'_, U, E> {
+    /// Opens the given modal as the initial response to this interaction and waits for the user to
+    /// submit it. The submission is acknowledged without sending a message, so you can respond
+    /// with a regular followup afterwards.
+    ///
+    /// Returns None if the user didn't submit the modal within `timeout` (one hour by default),
+    /// or if this is an autocomplete interaction.
+    ///
+    /// Fails if the interaction has already been responded to or deferred, since Discord only
+    /// accepts modals as the initial response.
+    pub async fn execute_modal<M: Modal>(
+        self,
+        timeout: Option<std::time::Duration>,
+    ) -> Result<Option<M>, serenity::Error> {
+        let interaction = match self.interaction {
+            crate::ApplicationCommandOrAutocompleteInteraction::ApplicationCommand(x) => x,
+            crate::ApplicationCommandOrAutocompleteInteraction::Autocomplete(_) => return Ok(None),
+        };
+        let responder = self.responder(interaction);
+        if !responder.claim_initial_response() {
+            return Err(serenity::Error::Other(
+                "a modal can only be sent as the initial interaction response",
+            ));
+        }
+
+        let custom_id = interaction.id.0.to_string();
+        let create_custom_id = custom_id.clone();
+        let response =
+            crate::InitialResponse::Modal(Box::new(move |b| M::create(b, create_custom_id)));
+        responder.send_initial_response(response).await?;
+
+        let author_id = interaction.user.id;
+        let response = self
+            .framework
+            .wait_for_interaction(
+                move |i| match i {
+                    serenity::Interaction::ModalSubmit(modal) => {
+                        modal.user.id == author_id && modal.data.custom_id == custom_id
+                    }
+                    _ => false,
+                },
+                timeout.unwrap_or(DEFAULT_MODAL_TIMEOUT),
+            )
+            .await;
+        let response = match response {
+            Some(serenity::Interaction::ModalSubmit(x)) => x,
+            _ => return Ok(None),
+        };
+
+        // Acknowledge the submission so that Discord closes the modal
+        let request = crate::ResponseRequest::InitialResponse {
+            interaction: crate::InteractionRef::Modal(&response),
+            response: crate::InitialResponse::DeferredUpdate,
+        };
+        self.framework.send_response(self.discord, request).await?;
+
+        M::parse(response.data.clone())
+            .map(Some)
+            .map_err(serenity::Error::Other)
+    }
+}
+
+#[test]
+fn test_find_modal_text() {
+    use serenity::json::prelude::*;
+
+    let mut data: serenity::ModalSubmitInteractionData = from_value(serenity::json::json!({
+        "custom_id": "123",
+        "components": [
+            { "type": 1, "components": [{ "type": 4, "custom_id": "title", "value": "Hello" }] },
+            { "type": 1, "components": [{ "type": 4, "custom_id": "body", "value": "" }] },
+        ],
+    }))
+    .unwrap();
+
+    assert_eq!(
+        _find_modal_text(&mut data, "title").as_deref(),
+        Some("Hello")
+    );
+    assert_eq!(_find_modal_text(&mut data, "body"), None);
+    assert_eq!(_find_modal_text(&mut data, "missing"), None);
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_execute_modal() {
+    /// Modal with a single text input
+    struct Feedback(Option<String>);
+    impl Modal for Feedback {
+        fn create(_: &mut serenity::CreateInteractionResponse, _: String) {}
+        fn parse(mut data: serenity::ModalSubmitInteractionData) -> Result<Self, &'static str> {
+            Ok(Self(_find_modal_text(&mut data, "text")))
+        }
+    }
+
+    let harness = crate::testing::TestHarness::new(
+        crate::FrameworkOptions {
+            commands: vec![crate::Command::<(), String> {
+                name: "feedback",
+                qualified_name: "feedback".into(),
+                slash_action: Some(|ctx, _| {
+                    Box::pin(async move {
+                        let feedback = ctx.execute_modal::<Feedback>(None).await.unwrap();
+                        let text = feedback.and_then(|x| x.0).unwrap_or_default();
+                        let _ = crate::say_reply(ctx.into(), text).await;
+                        Ok(())
+                    })
+                }),
+                ..Default::default()
+            }],
+            ..Default::default()
+        },
+        (),
+    );
+
+    let interaction = harness.application_command("feedback", serenity::json::json!([]));
+    let custom_id = interaction.id.to_string();
+    let event = crate::Event::InteractionCreate {
+        interaction: serenity::Interaction::ApplicationCommand(interaction),
+    };
+    let run_command = harness.dispatch(&event);
+    // The command is suspended waiting for the submission by the time the modal is submitted
+    let submit = harness.submit_modal(&custom_id, &[("text", "Great bot")]);
+    tokio::join!(run_command, submit);
+
+    let kinds = harness
+        .take_replies()
+        .into_iter()
+        .map(|reply| (reply.kind, reply.content))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        vec![
+            (crate::testing::CapturedReplyKind::Modal, None),
+            (crate::testing::CapturedReplyKind::DeferredResponse, None),
+            (
+                crate::testing::CapturedReplyKind::Followup,
+                Some("Great bot".into())
+            ),
+        ]
+    );
+}
@@ -126,15 +126,33 @@ pub struct PrefixFrameworkOptions<U, E> {
     pub execute_self_messages: bool,
     /// Whether command names should be compared case-insensitively.
     pub case_insensitive_commands: bool,
-    /* // TODO: implement
-    /// Whether to invoke help command when someone sends a message with just a bot mention
+    /// If true, messages which start with a prefix but don't match any command raise
+    /// [`crate::FrameworkError::UnknownCommand`], along with similarly named commands the user may
+    /// have meant to invoke. By default, such messages are silently ignored.
+    pub suggest_similar_commands: bool,
+    /// Whether to invoke [`Self::help_command`] when someone sends a message with just a bot
+    /// mention or prefix
     pub help_when_mentioned: bool,
-    /// The bot's general help command. Currently used for [`Self::help_when_mentioned`].
-    pub help_commmand: Option<Command<U, E>>,
-    // /// The bot's help command for individial commands. Currently used when a command group without
-    // /// any specific subcommand is invoked. This command is expected to take the command name as a
-    // /// single parameter
-    // pub command_specific_help_commmand: Option<Command<U, E>>, */
+    /// Name of the bot's general help command in [`crate::FrameworkOptions::commands`]. Used for
+    /// [`Self::help_when_mentioned`] and when a command group is invoked without a valid
+    /// subcommand. In the latter case, the help command is invoked with the qualified name of the
+    /// group as its arguments, so it should accept the command name as a `#[rest]` parameter
+    ///
+    /// ```rust
+    /// # type Error = Box<dyn std::error::Error + Send + Sync>;
+    /// # #[poise::command(prefix_command)]
+    /// # async fn help(ctx: poise::Context<'_, (), Error>, #[rest] command: Option<String>) -> Result<(), Error> { Ok(()) }
+    /// let options = poise::FrameworkOptions::<(), Error> {
+    ///     commands: vec![help()],
+    ///     prefix_options: poise::PrefixFrameworkOptions {
+    ///         help_when_mentioned: true,
+    ///         help_command: Some("help".into()),
+    ///         ..Default::default()
+    ///     },
+    ///     ..Default::default()
+    /// };
+    /// ```
+    pub help_command: Option<String>,
 }
 
 impl<U: std::fmt::Debug, E: std::fmt::Debug> std::fmt::Debug for PrefixFrameworkOptions<U, E> {
@@ -150,6 +168,9 @@ impl<U: std::fmt::Debug, E: std::fmt::Debug> std::fmt::Debug for PrefixFramework
             ignore_edit_tracker_cache,
             execute_self_messages,
             case_insensitive_commands,
+            suggest_similar_commands,
+            help_when_mentioned,
+            help_command,
         } = self;
 
         f.debug_struct("PrefixFrameworkOptions")
@@ -166,6 +187,9 @@ impl<U: std::fmt::Debug, E: std::fmt::Debug> std::fmt::Debug for PrefixFramework
             .field("ignore_edit_tracker_cache", ignore_edit_tracker_cache)
             .field("execute_self_messages", execute_self_messages)
             .field("case_insensitive_commands", case_insensitive_commands)
+            .field("suggest_similar_commands", suggest_similar_commands)
+            .field("help_when_mentioned", help_when_mentioned)
+            .field("help_command", help_command)
             .finish()
     }
 }
@@ -183,9 +207,9 @@ impl<U, E> Default for PrefixFrameworkOptions<U, E> {
             ignore_edit_tracker_cache: false,
             execute_self_messages: false,
             case_insensitive_commands: true,
-            // help_when_mentioned: true,
-            // help_commmand: None,
-            // command_specific_help_commmand: None,
+            suggest_similar_commands: false,
+            help_when_mentioned: true,
+            help_command: None,
         }
     }
 }
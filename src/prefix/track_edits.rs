@@ -150,21 +150,66 @@ impl EditTracker {
     }
 }
 
-/// Prefix-specific reply function. For more details, see [`crate::send_reply`].
-pub async fn send_prefix_reply<'a, U, E>(
-    ctx: crate::prefix::PrefixContext<'_, U, E>,
-    builder: impl for<'b> FnOnce(&'b mut crate::CreateReply<'a>) -> &'b mut crate::CreateReply<'a>,
-) -> Result<Box<serenity::Message>, serenity::Error> {
-    let mut reply = crate::CreateReply::default();
-    builder(&mut reply);
+/// Overwrites a prefix command response with the given reply. Anything not set in the reply, like
+/// content or components, is removed from the message
+pub(crate) fn edit_prefix_message<'a>(
+    reply: crate::CreateReply<'a>,
+    f: &mut serenity::EditMessage<'a>,
+) {
     let crate::CreateReply {
         content,
         embeds,
         attachments,
         components,
         ephemeral: _,
+        overflow: _,
     } = reply;
 
+    // Empty string resets content (happens when user replaces text with embed)
+    f.content(content.as_deref().unwrap_or(""));
+
+    f.set_embeds(embeds);
+
+    f.0.insert("attachments", serenity::json::json! { [] }); // reset attachments
+    for attachment in attachments {
+        f.attachment(attachment);
+    }
+
+    // When components is None, this will still be run to reset the message components
+    f.components(|f| {
+        if let Some(components) = components {
+            *f = components;
+        }
+        f
+    });
+}
+
+/// Sends one of the leading parts of a reply whose content was split because it was too long (see
+/// [`crate::OverflowPolicy::Split`])
+async fn send_prefix_reply_part<U, E>(
+    ctx: crate::prefix::PrefixContext<'_, U, E>,
+    part: String,
+) -> Result<(), serenity::Error> {
+    let reply = crate::CreateReply {
+        content: Some(part),
+        ..Default::default()
+    };
+    let request = crate::MessageRequest::CreateMessage {
+        channel_id: ctx.msg.channel_id,
+        reply,
+    };
+    ctx.framework.send_message(ctx.discord, request).await?;
+    Ok(())
+}
+
+/// Prefix-specific reply function. For more details, see [`crate::send_reply`].
+pub async fn send_prefix_reply<'a, U, E>(
+    ctx: crate::prefix::PrefixContext<'_, U, E>,
+    builder: impl for<'b> FnOnce(&'b mut crate::CreateReply<'a>) -> &'b mut crate::CreateReply<'a>,
+) -> Result<Box<serenity::Message>, serenity::Error> {
+    let mut reply = crate::CreateReply::default();
+    builder(&mut reply);
+
     let lock_edit_tracker = || {
         // If we definitely don't need to track this command invocation, stop
         let execute_untracked_edits = ctx
@@ -187,72 +232,36 @@ pub async fn send_prefix_reply<'a, U, E>(
         .and_then(|t| t.find_bot_response(ctx.msg.id))
         .cloned();
 
-    Ok(Box::new(if let Some(mut response) = existing_response {
-        response
-            .edit(ctx.discord, |f| {
-                // Empty string resets content (happens when user replaces text with embed)
-                f.content(content.as_deref().unwrap_or(""));
-
-                f.set_embeds(embeds);
-
-                f.0.insert("attachments", serenity::json::json! { [] }); // reset attachments
-                for attachment in attachments {
-                    f.attachment(attachment);
-                }
-
-                // When components is None, this will still be run to reset the message components
-                f.components(|f| {
-                    if let Some(components) = components {
-                        *f = components;
-                    }
-                    f
-                });
-
-                f
-            })
-            .await?;
-
-        // If the entry still exists after the await, update it to the new contents
-        if let Some(response_entry) = lock_edit_tracker()
-            .as_mut()
-            .and_then(|t| t.find_bot_response_mut(ctx.msg.id))
-        {
-            *response_entry = response.clone();
-        }
+    // An existing response can only be edited, so overlong content can't be split then
+    let leading_parts = reply.apply_overflow_policy(existing_response.is_none(), true);
+    for part in leading_parts {
+        send_prefix_reply_part(ctx, part).await?;
+    }
 
-        response
-    } else {
-        let new_response = ctx
-            .msg
-            .channel_id
-            .send_message(ctx.discord, |m| {
-                if let Some(content) = content {
-                    m.content(content);
-                }
-                m.set_embeds(embeds);
-                if let Some(allowed_mentions) = &ctx.framework.options().allowed_mentions {
-                    m.allowed_mentions(|m| {
-                        *m = allowed_mentions.clone();
-                        m
-                    });
-                }
-                if let Some(components) = components {
-                    m.components(|c| {
-                        c.0 = components.0;
-                        c
-                    });
-                }
+    let request = match &existing_response {
+        Some(response) => crate::MessageRequest::EditMessage {
+            channel_id: response.channel_id,
+            message_id: response.id,
+            reply,
+        },
+        None => crate::MessageRequest::CreateMessage {
+            channel_id: ctx.msg.channel_id,
+            reply,
+        },
+    };
+    let response = ctx.framework.send_message(ctx.discord, request).await?;
 
-                for attachment in attachments {
-                    m.add_file(attachment);
-                }
-                m
-            })
-            .await?;
-        if let Some(track_edits) = &mut lock_edit_tracker() {
-            track_edits.register_response(ctx.msg.clone(), new_response.clone());
+    // If the invocation is tracked, update its entry to the new response. An existing entry may
+    // have been purged during the await
+    if let Some(edit_tracker) = &mut lock_edit_tracker() {
+        match edit_tracker.find_bot_response_mut(ctx.msg.id) {
+            Some(response_entry) => *response_entry = response.clone(),
+            None if existing_response.is_none() => {
+                edit_tracker.register_response(ctx.msg.clone(), response.clone())
+            }
+            None => {}
         }
+    }
 
-        new_response
-    }))
+    Ok(Box::new(response))
 }
@@ -0,0 +1,535 @@
+//! Infrastructure for replying, i.e. sending a message in a command context
+//!
+//! This file dispatches to command-type specific reply code, which is in the prefix and slash
+//! modules
+
+mod sink;
+pub(crate) use sink::*;
+
+use crate::serenity_prelude as serenity;
+
+/// Maximum number of characters in the content of a Discord message
+const MESSAGE_CHARACTER_LIMIT: usize = 2000;
+
+/// What to do when the content of a reply exceeds Discord's limit of 2000 characters
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OverflowPolicy {
+    /// Split the content into multiple messages at line boundaries. Code blocks that are split
+    /// are closed at the end of one message and reopened in the next. Embeds, attachments and
+    /// components are sent with the last message.
+    ///
+    /// When editing a message, where splitting isn't possible, the content is truncated instead.
+    Split,
+    /// Upload the content as a `message.txt` attachment instead.
+    ///
+    /// When editing an interaction response, where attachments aren't supported, the content is
+    /// truncated instead.
+    Attachment,
+    /// Cut off the content at the character limit and end it with an ellipsis
+    Truncate,
+}
+
+#[allow(clippy::derivable_impls)] // #[default] on enum variants needs a newer Rust version
+impl Default for OverflowPolicy {
+    fn default() -> Self {
+        Self::Split
+    }
+}
+
+/// Message builder that abstracts over prefix and application command responses
+#[derive(Default)]
+pub struct CreateReply<'a> {
+    /// Message content
+    pub content: Option<String>,
+    /// Embeds, if present
+    pub embeds: Vec<serenity::CreateEmbed>,
+    /// Message attachments
+    pub attachments: Vec<serenity::AttachmentType<'a>>,
+    /// Whether the message is ephemeral (only has an effect in application commands)
+    pub ephemeral: bool,
+    /// Message components, that is, buttons
+    pub components: Option<serenity::CreateComponents>,
+    /// What to do if the content is too long for a single message
+    pub overflow: OverflowPolicy,
+}
+
+impl<'a> CreateReply<'a> {
+    /// Set the content of the message.
+    pub fn content(&mut self, content: impl Into<String>) -> &mut Self {
+        self.content = Some(content.into());
+        self
+    }
+
+    /// Adds an embed to the message.
+    ///
+    /// Existing embeds are kept.
+    pub fn embed(
+        &mut self,
+        f: impl FnOnce(&mut serenity::CreateEmbed) -> &mut serenity::CreateEmbed,
+    ) -> &mut Self {
+        let mut embed = serenity::CreateEmbed::default();
+        f(&mut embed);
+        self.embeds.push(embed);
+        self
+    }
+
+    /// Set components (buttons) for this message.
+    ///
+    /// Any previously set components will be overwritten.
+    pub fn components(
+        &mut self,
+        f: impl FnOnce(&mut serenity::CreateComponents) -> &mut serenity::CreateComponents,
+    ) -> &mut Self {
+        let mut components = serenity::CreateComponents::default();
+        f(&mut components);
+        self.components = Some(components);
+        self
+    }
+
+    /// Add an attachment.
+    ///
+    /// If this is a slash command's initial response, the response is deferred and the message is
+    /// sent as a followup instead, since files can't be attached to the initial response directly.
+    ///
+    /// This will not have an effect when editing an interaction response!
+    pub fn attachment(&mut self, attachment: serenity::AttachmentType<'a>) -> &mut Self {
+        self.attachments.push(attachment);
+        self
+    }
+
+    /// Toggles whether the message is an ephemeral response (only invoking user can see it).
+    ///
+    /// This only has an effect in slash commands!
+    ///
+    /// If this is the initial response and this response
+    /// has previously been deferred, the ephemerality is decided by the defer operation. I.e.
+    /// if you deferred the response without enabling ephemeral, the initial response will not be
+    /// ephemeral.
+    pub fn ephemeral(&mut self, ephemeral: bool) -> &mut Self {
+        self.ephemeral = ephemeral;
+        self
+    }
+
+    /// Sets what to do if the content is longer than Discord's limit of 2000 characters.
+    ///
+    /// Defaults to [`OverflowPolicy::Split`]
+    pub fn overflow(&mut self, overflow: OverflowPolicy) -> &mut Self {
+        self.overflow = overflow;
+        self
+    }
+
+    /// Applies the [`OverflowPolicy`] if the content is too long. If the policy is to split the
+    /// content, the last part is kept as content of this reply, and the preceding parts are
+    /// returned so they can be sent as separate messages first.
+    ///
+    /// `can_split` and `can_attach` tell whether the reply will be sent in a way that allows
+    /// sending multiple messages or attachments; if not, the content is truncated instead
+    pub(crate) fn apply_overflow_policy(
+        &mut self,
+        can_split: bool,
+        can_attach: bool,
+    ) -> Vec<String> {
+        let content = match &mut self.content {
+            Some(content) if content.chars().count() > MESSAGE_CHARACTER_LIMIT => content,
+            _ => return Vec::new(),
+        };
+
+        match self.overflow {
+            OverflowPolicy::Split if can_split => {
+                let mut parts = split_message(content, MESSAGE_CHARACTER_LIMIT);
+                *content = parts.pop().unwrap_or_default();
+                parts
+            }
+            OverflowPolicy::Attachment if can_attach => {
+                let content = self.content.take().unwrap_or_default();
+                self.attachments.push(serenity::AttachmentType::Bytes {
+                    data: content.into_bytes().into(),
+                    filename: String::from("message.txt"),
+                });
+                Vec::new()
+            }
+            _ => {
+                let ellipsis = '…';
+                *content = content
+                    .chars()
+                    .take(MESSAGE_CHARACTER_LIMIT - 1)
+                    .chain(std::iter::once(ellipsis))
+                    .collect();
+                Vec::new()
+            }
+        }
+    }
+}
+
+/// Splits the text into parts of at most `limit` characters, preferably at line boundaries.
+///
+/// If a code block spans multiple parts, it is closed at the end of one part and reopened (with
+/// the same language) at the start of the next.
+fn split_message(text: &str, limit: usize) -> Vec<String> {
+    /// Appended to a part which ends inside a code block
+    const FENCE_CLOSE: &str = "\n```";
+
+    // Lines longer than this are split mid-line. Leaves room for reopening and closing a code
+    // block around the line
+    let max_line_len = limit / 2;
+
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut current_len = 0;
+    // The line that opened the code block we're currently in, e.g. "```rust"
+    let mut open_fence: Option<String> = None;
+
+    for line in text.split('\n') {
+        let chars = line.chars().collect::<Vec<_>>();
+        let pieces = chars
+            .chunks(max_line_len.max(1))
+            .map(|c| c.iter().collect::<String>());
+        let pieces = if chars.is_empty() {
+            vec![String::new()]
+        } else {
+            pieces.collect()
+        };
+
+        for piece in pieces {
+            // Only lines consisting of just a fence toggle the code block, not inline ones
+            // like "```x```"
+            let trimmed = piece.trim();
+            let is_fence = match &open_fence {
+                Some(_) => trimmed == "```",
+                None => trimmed.starts_with("```") && !trimmed[3..].contains('`'),
+            };
+            let fence_after = match (&open_fence, is_fence) {
+                (Some(_), true) => None,
+                (None, true) => Some(piece.trim().to_owned()),
+                (fence, false) => fence.clone(),
+            };
+
+            let piece_len = piece.chars().count();
+            let separator_len = if current.is_empty() { 0 } else { 1 };
+            let reserved_len = if fence_after.is_some() {
+                FENCE_CLOSE.len()
+            } else {
+                0
+            };
+            if !current.is_empty() && current_len + separator_len + piece_len + reserved_len > limit
+            {
+                if open_fence.is_some() {
+                    current += FENCE_CLOSE;
+                }
+                parts.push(std::mem::take(&mut current));
+                current_len = 0;
+                if let Some(fence) = &open_fence {
+                    current += fence;
+                    current_len = fence.chars().count();
+                }
+            }
+
+            if !current.is_empty() {
+                current.push('\n');
+                current_len += 1;
+            }
+            current += &piece;
+            current_len += piece_len;
+            open_fence = fence_after;
+        }
+    }
+    parts.push(current);
+
+    parts
+}
+
+/// Returned from [`send_reply`] to retrieve, edit or delete the sent message.
+///
+/// For prefix commands, you can retrieve the sent message directly. For slash commands, Discord
+/// requires a network request.
+pub struct ReplyHandle<'a>(ReplyHandleInner<'a>);
+
+/// The contents of a [`ReplyHandle`], kept private so that they can change without breaking
+/// downstream code
+enum ReplyHandleInner<'a> {
+    /// When sending a normal message, Discord returns the message object directly
+    Prefix(Box<serenity::Message>),
+    /// When sending an application command response, you need to request the message object
+    /// seperately
+    Application {
+        /// Serenity HTTP instance that can be used to request the interaction response message
+        /// object
+        http: &'a serenity::Http,
+        /// Interaction which contains the necessary data to request the interaction response
+        /// message object
+        interaction: &'a serenity::ApplicationCommandInteraction,
+        /// If the reply was sent as a followup message, the followup message. None if the reply
+        /// is the initial interaction response
+        followup: Option<Box<serenity::Message>>,
+    },
+}
+
+impl ReplyHandle<'_> {
+    /// Retrieve the message object of the sent reply.
+    ///
+    /// Only needs to do an HTTP request in the application command initial response case
+    pub async fn message(self) -> Result<serenity::Message, serenity::Error> {
+        match self.0 {
+            ReplyHandleInner::Prefix(msg) => Ok(*msg),
+            ReplyHandleInner::Application {
+                followup: Some(msg),
+                ..
+            } => Ok(*msg),
+            ReplyHandleInner::Application {
+                http,
+                interaction,
+                followup: None,
+            } => interaction.get_interaction_response(http).await,
+        }
+    }
+
+    /// Replaces the contents of the sent reply with the given builder, for example to turn a
+    /// "Working..." message into the final result.
+    ///
+    /// Like with edit tracking, the message is overwritten: content, embeds and components that
+    /// aren't set in the builder are removed. The ephemerality of a message can't be changed.
+    ///
+    /// ```rust,no_run
+    /// # async fn _test(ctx: poise::Context<'_, (), ()>) -> Result<(), poise::serenity_prelude::Error> {
+    /// if let Some(reply) = ctx.say("Working...").await? {
+    ///     // ... do the work ...
+    ///     reply.edit(ctx, |b| b.content("Done!")).await?;
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub async fn edit<'att, U, E>(
+        &self,
+        ctx: crate::Context<'_, U, E>,
+        builder: impl for<'b> FnOnce(&'b mut CreateReply<'att>) -> &'b mut CreateReply<'att>,
+    ) -> Result<(), serenity::Error> {
+        let mut reply = CreateReply::default();
+        builder(&mut reply);
+        // An edit can't be split, and serenity doesn't support attachments in interaction
+        // response edits
+        let can_attach = matches!(self.0, ReplyHandleInner::Prefix(_));
+        reply.apply_overflow_policy(false, can_attach);
+
+        let framework = ctx.framework();
+        match &self.0 {
+            ReplyHandleInner::Prefix(msg) => {
+                let request = MessageRequest::EditMessage {
+                    channel_id: msg.channel_id,
+                    message_id: msg.id,
+                    reply,
+                };
+                let edited = framework.send_message(ctx.discord(), request).await?;
+
+                // Keep the edit tracker's copy of the response up to date
+                if let (crate::Context::Prefix(ctx), Some(edit_tracker)) =
+                    (ctx, &framework.options().prefix_options.edit_tracker)
+                {
+                    let mut edit_tracker = edit_tracker.write().unwrap();
+                    if let Some(response) = edit_tracker.find_bot_response_mut(ctx.msg.id) {
+                        if response.id == edited.id {
+                            *response = edited;
+                        }
+                    }
+                }
+            }
+            ReplyHandleInner::Application {
+                interaction,
+                followup: Some(msg),
+                ..
+            } => {
+                let request = MessageRequest::EditFollowup {
+                    interaction: InteractionRef::Command(interaction),
+                    message_id: msg.id,
+                    reply,
+                };
+                framework.send_message(ctx.discord(), request).await?;
+            }
+            ReplyHandleInner::Application {
+                interaction,
+                followup: None,
+                ..
+            } => {
+                let request = ResponseRequest::EditInitialResponse {
+                    interaction: InteractionRef::Command(interaction),
+                    reply,
+                };
+                framework.send_response(ctx.discord(), request).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Deletes the sent reply
+    pub async fn delete<U, E>(&self, ctx: crate::Context<'_, U, E>) -> Result<(), serenity::Error> {
+        let request = match &self.0 {
+            ReplyHandleInner::Prefix(msg) => ResponseRequest::DeleteMessage {
+                channel_id: msg.channel_id,
+                message_id: msg.id,
+            },
+            ReplyHandleInner::Application {
+                interaction,
+                followup: Some(msg),
+                ..
+            } => ResponseRequest::DeleteFollowup {
+                interaction: InteractionRef::Command(interaction),
+                message_id: msg.id,
+            },
+            ReplyHandleInner::Application {
+                interaction,
+                followup: None,
+                ..
+            } => ResponseRequest::DeleteInitialResponse {
+                interaction: InteractionRef::Command(interaction),
+            },
+        };
+        ctx.framework().send_response(ctx.discord(), request).await
+    }
+}
+
+/// Send a message in the given context: normal message if prefix command, interaction response
+/// if application command.
+///
+/// If you just want to send a string, use [`say_reply`].
+///
+/// ```rust,no_run
+/// # #[tokio::main] async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # let ctx: poise::Context<'_, (), ()> = todo!();
+/// ctx.send(|f| f
+///     .content("Works for slash and prefix commands")
+///     .embed(|f| f
+///         .title("Much versatile, very wow")
+///         .description("I need more documentation ok?")
+///     )
+///     .ephemeral(true) // this one only applies in application commands though
+/// ).await?;
+/// # Ok(()) }
+/// ```
+pub async fn send_reply<'a, U, E>(
+    ctx: crate::Context<'_, U, E>,
+    builder: impl for<'b> FnOnce(&'b mut CreateReply<'a>) -> &'b mut CreateReply<'a>,
+) -> Result<Option<ReplyHandle<'_>>, serenity::Error> {
+    Ok(match ctx {
+        crate::Context::Prefix(ctx) => Some(ReplyHandle(ReplyHandleInner::Prefix(
+            crate::send_prefix_reply(ctx, builder).await?,
+        ))),
+        crate::Context::Application(ctx) => {
+            let followup = crate::send_application_reply(ctx, builder).await?;
+
+            if let crate::ApplicationCommandOrAutocompleteInteraction::ApplicationCommand(
+                interaction,
+            ) = &ctx.interaction
+            {
+                Some(ReplyHandle(ReplyHandleInner::Application {
+                    interaction,
+                    http: &ctx.discord.http,
+                    followup,
+                }))
+            } else {
+                None
+            }
+        }
+    })
+}
+
+/// Shorthand of [`send_reply`] for text-only messages
+pub async fn say_reply<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    text: impl Into<String>,
+) -> Result<Option<ReplyHandle<'_>>, serenity::Error> {
+    send_reply(ctx, |m| m.content(text.into())).await
+}
+
+#[test]
+fn test_split_message() {
+    assert_eq!(split_message("short", 20), vec!["short"]);
+    assert_eq!(
+        split_message("line one\nline two\nline three", 20),
+        vec!["line one\nline two", "line three"],
+    );
+    // Overlong lines are split mid-line
+    assert_eq!(
+        split_message("aaaaaaaaaaaa", 8),
+        vec!["aaaa", "aaaa", "aaaa"]
+    );
+    // Code blocks are closed and reopened across parts
+    assert_eq!(
+        split_message("intro\n```rust\nlet a;\nlet b;\n```\noutro", 24),
+        vec!["intro\n```rust\nlet a;\n```", "```rust\nlet b;\n```\noutro"],
+    );
+    // Inline fences don't open or close a code block
+    assert_eq!(
+        split_message("```x```\nline one\nline two", 18),
+        vec!["```x```\nline one", "line two"],
+    );
+    for part in split_message(&"```\nsome code here\n".repeat(50), 100) {
+        assert!(part.chars().count() <= 100);
+    }
+}
+
+#[test]
+fn test_apply_overflow_policy() {
+    let long_content = "a\n".repeat(1500);
+
+    let mut reply = CreateReply::default();
+    reply.content(long_content.clone());
+    let leading_parts = reply.apply_overflow_policy(true, true);
+    assert_eq!(leading_parts.len(), 1);
+    assert_eq!(
+        format!("{}\n{}", leading_parts[0], reply.content.unwrap()),
+        long_content
+    );
+
+    let mut reply = CreateReply::default();
+    reply.content(long_content.clone());
+    assert!(reply.apply_overflow_policy(false, true).is_empty());
+    assert_eq!(reply.content.unwrap().chars().count(), 2000);
+
+    let mut reply = CreateReply::default();
+    reply
+        .content(long_content)
+        .overflow(OverflowPolicy::Attachment);
+    assert!(reply.apply_overflow_policy(true, true).is_empty());
+    assert_eq!(reply.content, None);
+    assert_eq!(reply.attachments.len(), 1);
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_edit_updates_edit_tracker() {
+    let harness = crate::testing::TestHarness::new(
+        crate::FrameworkOptions {
+            commands: vec![crate::Command::<(), String> {
+                name: "work",
+                qualified_name: "work".into(),
+                prefix_action: Some(|ctx, _| {
+                    Box::pin(async move {
+                        let ctx = crate::Context::Prefix(ctx);
+                        let reply = say_reply(ctx, "Working...").await.unwrap().unwrap();
+                        reply.edit(ctx, |b| b.content("Done")).await.unwrap();
+                        Ok(())
+                    })
+                }),
+                ..Default::default()
+            }],
+            prefix_options: crate::PrefixFrameworkOptions {
+                prefix: Some("~".into()),
+                edit_tracker: Some(crate::EditTracker::for_timespan(
+                    std::time::Duration::from_secs(60),
+                )),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        (),
+    );
+
+    let msg = harness.send_message("~work").await;
+    let edit_tracker = harness
+        .framework()
+        .options()
+        .prefix_options
+        .edit_tracker
+        .as_ref();
+    let edit_tracker = edit_tracker.unwrap().read().unwrap();
+    let response = edit_tracker.find_bot_response(msg.id).unwrap();
+    assert_eq!(response.content, "Done");
+}
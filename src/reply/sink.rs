@@ -0,0 +1,353 @@
+//! The single place where responses leave the framework, so that they can be recorded instead of
+//! sent by `TestHarness`
+
+use crate::serenity_prelude as serenity;
+
+/// Abstracts over references to the interaction types that can be responded to
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum InteractionRef<'a> {
+    /// An application command interaction
+    Command(&'a serenity::ApplicationCommandInteraction),
+    /// A message component interaction
+    Component(&'a serenity::MessageComponentInteraction),
+    /// A modal submit interaction
+    Modal(&'a serenity::ModalSubmitInteraction),
+}
+
+impl InteractionRef<'_> {
+    /// Returns the channel that the interaction was used in
+    #[cfg(any(test, feature = "testing"))]
+    pub(crate) fn channel_id(self) -> serenity::ChannelId {
+        match self {
+            Self::Command(x) => x.channel_id,
+            Self::Component(x) => x.channel_id,
+            Self::Modal(x) => x.channel_id,
+        }
+    }
+}
+
+/// Calls the same method on whichever interaction type is wrapped in the [`InteractionRef`]
+macro_rules! with_interaction {
+    ($interaction:expr, |$x:ident| $body:expr) => {
+        match $interaction {
+            InteractionRef::Command($x) => $body,
+            InteractionRef::Component($x) => $body,
+            InteractionRef::Modal($x) => $body,
+        }
+    };
+}
+
+/// The different kinds of initial responses to an interaction
+pub(crate) enum InitialResponse<'att> {
+    /// Respond with a new message
+    Message(crate::CreateReply<'att>),
+    /// Show a "thinking" state, which is replaced by the first followup message
+    DeferredMessage {
+        /// Whether the followup will be ephemeral
+        ephemeral: bool,
+    },
+    /// Edit the message that contains the used component
+    UpdateMessage(crate::CreateReply<'att>),
+    /// Acknowledge the interaction without changing anything yet
+    DeferredUpdate,
+    /// Open a modal. The closure fills in the response builder
+    Modal(Box<dyn FnOnce(&mut serenity::CreateInteractionResponse) + Send + 'att>),
+}
+
+/// A request to Discord which sends or edits a message, see [`ResponseSink::send_message`]
+pub(crate) enum MessageRequest<'a, 'att> {
+    /// Send a new message in a channel
+    CreateMessage {
+        /// Channel to send the message in
+        channel_id: serenity::ChannelId,
+        /// The message
+        reply: crate::CreateReply<'att>,
+    },
+    /// Overwrite a message, see [`crate::edit_prefix_message`]
+    EditMessage {
+        /// Channel of the message
+        channel_id: serenity::ChannelId,
+        /// The message to edit
+        message_id: serenity::MessageId,
+        /// The new message
+        reply: crate::CreateReply<'att>,
+    },
+    /// Edit a message, leaving the parts that aren't set in the reply unchanged, see
+    /// [`crate::ComponentContext::update_message`]
+    UpdateMessage {
+        /// Channel of the message
+        channel_id: serenity::ChannelId,
+        /// The message to edit
+        message_id: serenity::MessageId,
+        /// The changes to the message
+        reply: crate::CreateReply<'att>,
+    },
+    /// Send a followup message to an interaction
+    CreateFollowup {
+        /// The interaction to respond to
+        interaction: InteractionRef<'a>,
+        /// The message
+        reply: crate::CreateReply<'att>,
+    },
+    /// Edit a followup message
+    EditFollowup {
+        /// The interaction that the followup belongs to
+        interaction: InteractionRef<'a>,
+        /// The followup to edit
+        message_id: serenity::MessageId,
+        /// The new message
+        reply: crate::CreateReply<'att>,
+    },
+}
+
+/// Any other request to Discord which responds to an interaction or deletes a response, see
+/// [`ResponseSink::send`]
+pub(crate) enum ResponseRequest<'a, 'att> {
+    /// Respond to an interaction for the first time
+    InitialResponse {
+        /// The interaction to respond to
+        interaction: InteractionRef<'a>,
+        /// The response
+        response: InitialResponse<'att>,
+    },
+    /// Edit the initial response to an interaction
+    EditInitialResponse {
+        /// The interaction whose response to edit
+        interaction: InteractionRef<'a>,
+        /// The new response
+        reply: crate::CreateReply<'att>,
+    },
+    /// Delete the initial response to an interaction
+    DeleteInitialResponse {
+        /// The interaction whose response to delete
+        interaction: InteractionRef<'a>,
+    },
+    /// Delete a followup message
+    DeleteFollowup {
+        /// The interaction that the followup belongs to
+        interaction: InteractionRef<'a>,
+        /// The followup to delete
+        message_id: serenity::MessageId,
+    },
+    /// Delete a message
+    DeleteMessage {
+        /// Channel of the message
+        channel_id: serenity::ChannelId,
+        /// The message to delete
+        message_id: serenity::MessageId,
+    },
+}
+
+/// Executes [`MessageRequest`]s and [`ResponseRequest`]s. The framework sends all of its
+/// responses through this, so that `TestHarness` can swap in a recorder
+#[async_trait::async_trait]
+pub(crate) trait ResponseSink: Send + Sync {
+    /// Sends or edits a message and returns it
+    async fn send_message(
+        &self,
+        discord: &serenity::Context,
+        allowed_mentions: Option<&serenity::CreateAllowedMentions>,
+        request: MessageRequest<'_, '_>,
+    ) -> Result<serenity::Message, serenity::Error>;
+
+    /// Responds to an interaction or deletes a response
+    async fn send(
+        &self,
+        discord: &serenity::Context,
+        allowed_mentions: Option<&serenity::CreateAllowedMentions>,
+        request: ResponseRequest<'_, '_>,
+    ) -> Result<(), serenity::Error>;
+}
+
+/// The [`ResponseSink`] of a framework that is connected to Discord
+pub(crate) struct HttpSink;
+
+#[async_trait::async_trait]
+impl ResponseSink for HttpSink {
+    async fn send_message(
+        &self,
+        discord: &serenity::Context,
+        allowed_mentions: Option<&serenity::CreateAllowedMentions>,
+        request: MessageRequest<'_, '_>,
+    ) -> Result<serenity::Message, serenity::Error> {
+        match request {
+            MessageRequest::CreateMessage { channel_id, reply } => {
+                let crate::CreateReply {
+                    content,
+                    embeds,
+                    attachments,
+                    components,
+                    ephemeral: _,
+                    overflow: _,
+                } = reply;
+
+                channel_id
+                    .send_message(discord, |m| {
+                        if let Some(content) = content {
+                            m.content(content);
+                        }
+                        m.set_embeds(embeds);
+                        if let Some(allowed_mentions) = allowed_mentions {
+                            m.allowed_mentions(|m| {
+                                *m = allowed_mentions.clone();
+                                m
+                            });
+                        }
+                        if let Some(components) = components {
+                            m.components(|c| {
+                                c.0 = components.0;
+                                c
+                            });
+                        }
+
+                        for attachment in attachments {
+                            m.add_file(attachment);
+                        }
+                        m
+                    })
+                    .await
+            }
+            MessageRequest::EditMessage {
+                channel_id,
+                message_id,
+                reply,
+            } => {
+                channel_id
+                    .edit_message(discord, message_id, |f| {
+                        crate::edit_prefix_message(reply, f);
+                        f
+                    })
+                    .await
+            }
+            MessageRequest::UpdateMessage {
+                channel_id,
+                message_id,
+                reply,
+            } => {
+                let crate::CreateReply {
+                    content,
+                    embeds,
+                    attachments: _, // not supported in message updates, like in the initial response
+                    components,
+                    ephemeral: _,
+                    overflow: _,
+                } = reply;
+
+                channel_id
+                    .edit_message(discord, message_id, |f| {
+                        if let Some(content) = content {
+                            f.content(content);
+                        }
+                        f.set_embeds(embeds);
+                        if let Some(components) = components {
+                            f.components(|c| {
+                                *c = components;
+                                c
+                            });
+                        }
+                        f
+                    })
+                    .await
+            }
+            MessageRequest::CreateFollowup { interaction, reply } => {
+                with_interaction!(interaction, |x| {
+                    x.create_followup_message(discord, |f| {
+                        crate::send_as_followup_response(reply, allowed_mentions, f);
+                        f
+                    })
+                    .await
+                })
+            }
+            MessageRequest::EditFollowup {
+                interaction,
+                message_id,
+                reply,
+            } => {
+                with_interaction!(interaction, |x| {
+                    x.edit_followup_message(discord, message_id, |f| {
+                        crate::send_as_followup_response(reply, allowed_mentions, f);
+                        f
+                    })
+                    .await
+                })
+            }
+        }
+    }
+
+    async fn send(
+        &self,
+        discord: &serenity::Context,
+        allowed_mentions: Option<&serenity::CreateAllowedMentions>,
+        request: ResponseRequest<'_, '_>,
+    ) -> Result<(), serenity::Error> {
+        match request {
+            ResponseRequest::InitialResponse {
+                interaction,
+                response,
+            } => {
+                with_interaction!(interaction, |x| {
+                    x.create_interaction_response(discord, |r| match response {
+                        InitialResponse::Message(reply) => r
+                            .kind(serenity::InteractionResponseType::ChannelMessageWithSource)
+                            .interaction_response_data(|f| {
+                                crate::send_as_initial_response(reply, allowed_mentions, f);
+                                f
+                            }),
+                        InitialResponse::DeferredMessage { ephemeral } => {
+                            let mut flags =
+                                serenity::InteractionApplicationCommandCallbackDataFlags::empty();
+                            if ephemeral {
+                                flags |= serenity::InteractionApplicationCommandCallbackDataFlags::EPHEMERAL;
+                            }
+                            r.kind(
+                                serenity::InteractionResponseType::DeferredChannelMessageWithSource,
+                            )
+                            .interaction_response_data(|f| f.flags(flags))
+                        }
+                        InitialResponse::UpdateMessage(reply) => r
+                            .kind(serenity::InteractionResponseType::UpdateMessage)
+                            .interaction_response_data(|f| {
+                                crate::send_as_initial_response(reply, allowed_mentions, f);
+                                f
+                            }),
+                        InitialResponse::DeferredUpdate => {
+                            r.kind(serenity::InteractionResponseType::DeferredUpdateMessage)
+                        }
+                        InitialResponse::Modal(create) => {
+                            create(r);
+                            r
+                        }
+                    })
+                    .await
+                })
+            }
+            ResponseRequest::EditInitialResponse { interaction, reply } => {
+                with_interaction!(interaction, |x| {
+                    x.edit_original_interaction_response(discord, |f| {
+                        crate::send_as_edit(reply, allowed_mentions, f);
+                        f
+                    })
+                    .await
+                    .map(|_| ())
+                })
+            }
+            ResponseRequest::DeleteInitialResponse { interaction } => {
+                with_interaction!(interaction, |x| {
+                    x.delete_original_interaction_response(discord).await
+                })
+            }
+            ResponseRequest::DeleteFollowup {
+                interaction,
+                message_id,
+            } => {
+                with_interaction!(interaction, |x| {
+                    x.delete_followup_message(discord, message_id).await
+                })
+            }
+            ResponseRequest::DeleteMessage {
+                channel_id,
+                message_id,
+            } => channel_id.delete_message(discord, message_id).await,
+        }
+    }
+}
@@ -191,6 +191,34 @@ impl<T: SlashArgument + Sync> SlashArgumentHack<T> for &PhantomData<T> {
     }
 }
 
+/// Adds name localizations to the choices of a choice parameter, in the order in which the choices
+/// were added. Used by `#[derive(SlashChoiceParameter)]`
+#[doc(hidden)]
+pub fn _set_choice_name_localizations(
+    builder: &mut serenity::CreateApplicationCommandOption,
+    localizations: &[&[(&'static str, &'static str)]],
+) {
+    let choices = match builder.0.get_mut("choices").and_then(|c| c.as_array_mut()) {
+        Some(x) => x,
+        None => return,
+    };
+    for (choice, localizations) in choices.iter_mut().zip(localizations) {
+        if localizations.is_empty() {
+            continue;
+        }
+        let localizations = localizations
+            .iter()
+            .copied()
+            .collect::<std::collections::HashMap<_, _>>();
+        if let Some(choice) = choice.as_object_mut() {
+            choice.insert(
+                "name_localizations".into(),
+                serenity::json::json!(localizations),
+            );
+        }
+    }
+}
+
 /// Implements SlashArgumentHack for a model type that is represented in interactions via an ID
 macro_rules! impl_slash_argument {
     ($type:ty, $slash_param_type:ident) => {
@@ -10,7 +10,7 @@ use crate::serenity_prelude as serenity;
 
 /// Sends the message, specified via [`crate::CreateReply`], to the interaction initial response
 /// endpoint
-fn send_as_initial_response(
+pub(crate) fn send_as_initial_response(
     data: crate::CreateReply<'_>,
     allowed_mentions: Option<&serenity::CreateAllowedMentions>,
     f: &mut serenity::CreateInteractionResponseData,
@@ -18,9 +18,10 @@ fn send_as_initial_response(
     let crate::CreateReply {
         content,
         embeds,
-        attachments: _, // serenity doesn't support attachments in initial response yet
+        attachments: _, // serenity doesn't support this, so callers send a followup instead
         components,
         ephemeral,
+        overflow: _,
     } = data;
 
     if let Some(content) = content {
@@ -46,7 +47,7 @@ fn send_as_initial_response(
 
 /// Sends the message, specified via [`crate::CreateReply`], to the interaction followup response
 /// endpoint
-fn send_as_followup_response<'a>(
+pub(crate) fn send_as_followup_response<'a>(
     data: crate::CreateReply<'a>,
     allowed_mentions: Option<&serenity::CreateAllowedMentions>,
     f: &mut serenity::CreateInteractionResponseFollowup<'a>,
@@ -57,6 +58,7 @@ fn send_as_followup_response<'a>(
         attachments,
         components,
         ephemeral,
+        overflow: _,
     } = data;
 
     if let Some(content) = content {
@@ -83,7 +85,7 @@ fn send_as_followup_response<'a>(
 
 /// Sends the message, specified via [`crate::CreateReply`], to the interaction initial response
 /// edit endpoint
-fn send_as_edit<'a>(
+pub(crate) fn send_as_edit<'a>(
     data: crate::CreateReply<'a>,
     allowed_mentions: Option<&serenity::CreateAllowedMentions>,
     f: &mut serenity::EditInteractionResponse,
@@ -94,6 +96,7 @@ fn send_as_edit<'a>(
         attachments: _, // no support for attachment edits in serenity yet
         components,
         ephemeral: _, // can't edit ephemerality in retrospect
+        overflow: _,
     } = data;
 
     if let Some(content) = content {
@@ -119,14 +122,14 @@ fn send_as_edit<'a>(
 /// If a response to this interaction has already been sent, a
 /// [followup](serenity::ApplicationCommandInteraction::create_followup_message) is sent.
 ///
-/// No-op if autocomplete context
+/// Returns the followup message if one was sent. No-op if autocomplete context
 pub async fn send_application_reply<'a, U, E>(
     ctx: ApplicationContext<'_, U, E>,
     builder: impl for<'b> FnOnce(&'b mut crate::CreateReply<'a>) -> &'b mut crate::CreateReply<'a>,
-) -> Result<(), serenity::Error> {
+) -> Result<Option<Box<serenity::Message>>, serenity::Error> {
     let interaction = match ctx.interaction {
         crate::ApplicationCommandOrAutocompleteInteraction::ApplicationCommand(x) => x,
-        crate::ApplicationCommandOrAutocompleteInteraction::Autocomplete(_) => return Ok(()),
+        crate::ApplicationCommandOrAutocompleteInteraction::Autocomplete(_) => return Ok(None),
     };
 
     let mut data = crate::CreateReply {
@@ -135,40 +138,166 @@ pub async fn send_application_reply<'a, U, E>(
     };
     builder(&mut data);
 
-    let has_sent_initial_response = ctx
-        .has_sent_initial_response
-        .load(std::sync::atomic::Ordering::SeqCst);
-
-    let allowed_mentions = ctx.framework.options().allowed_mentions.as_ref();
-    if has_sent_initial_response {
-        if ctx.command.reuse_response {
-            interaction
-                .edit_original_interaction_response(ctx.discord, |f| {
-                    send_as_edit(data, allowed_mentions, f);
-                    f
-                })
-                .await?;
-        } else {
-            interaction
-                .create_followup_message(ctx.discord, |f| {
-                    send_as_followup_response(data, allowed_mentions, f);
-                    f
-                })
+    ctx.responder(interaction)
+        .send_reply(data, ctx.command.reuse_response)
+        .await
+}
+
+/// Everything needed to respond to an interaction. Both application commands and component
+/// handlers send their replies through this, so that they behave the same
+pub(crate) struct InteractionResponder<'a, U, E> {
+    /// Read-only reference to the framework
+    pub(crate) framework: &'a crate::Framework<U, E>,
+    /// Serenity's context, like HTTP or cache
+    pub(crate) discord: &'a serenity::Context,
+    /// The interaction to respond to
+    pub(crate) interaction: crate::InteractionRef<'a>,
+    /// Keeps track of whether an initial response has been sent
+    pub(crate) has_sent_initial_response: &'a std::sync::atomic::AtomicBool,
+    /// ID of the most recent followup message, which is edited when the response is reused. None
+    /// if responses are never reused
+    pub(crate) last_followup_id: Option<&'a std::sync::Mutex<Option<serenity::MessageId>>>,
+}
+impl<U, E> Clone for InteractionResponder<'_, U, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<U, E> Copy for InteractionResponder<'_, U, E> {}
+
+impl<U, E> InteractionResponder<'_, U, E> {
+    /// Sends a reply, splitting overlong content into multiple messages according to its
+    /// [`crate::OverflowPolicy`]. If `reuse_response` is set, the latest response is edited
+    /// instead of sending a new message
+    ///
+    /// Returns the followup message if one was sent
+    pub(crate) async fn send_reply(
+        self,
+        mut data: crate::CreateReply<'_>,
+        reuse_response: bool,
+    ) -> Result<Option<Box<serenity::Message>>, serenity::Error> {
+        // When the previous response is reused, overlong content can't be split or attached since
+        // the response is edited
+        let is_edit = reuse_response
+            && self
+                .has_sent_initial_response
+                .load(std::sync::atomic::Ordering::SeqCst);
+        let leading_parts = data.apply_overflow_policy(!is_edit, !is_edit);
+        // The parts of a split message must be sent as separate messages, not as edits
+        let reuse_response = reuse_response && leading_parts.is_empty();
+        for part in leading_parts {
+            let part = crate::CreateReply {
+                content: Some(part),
+                ephemeral: data.ephemeral,
+                ..Default::default()
+            };
+            self.send_message(part, false).await?;
+        }
+
+        self.send_message(data, reuse_response).await
+    }
+
+    /// Sends a single message: as the initial response, a followup, or, if `reuse_response` is
+    /// set, as an edit of the latest response
+    ///
+    /// Returns the followup message if one was sent
+    async fn send_message(
+        self,
+        data: crate::CreateReply<'_>,
+        mut reuse_response: bool,
+    ) -> Result<Option<Box<serenity::Message>>, serenity::Error> {
+        if !data.attachments.is_empty() {
+            // Serenity can't send attachments in the initial response. So we defer instead and
+            // send the reply as a followup, which then takes the place of the deferred response
+            if self.claim_initial_response() {
+                let response = crate::InitialResponse::DeferredMessage {
+                    ephemeral: data.ephemeral,
+                };
+                self.send_initial_response(response).await?;
+                reuse_response = false;
+            }
+        } else if self.claim_initial_response() {
+            self.send_initial_response(crate::InitialResponse::Message(data))
                 .await?;
+            return Ok(None);
         }
-    } else {
-        interaction
-            .create_interaction_response(ctx.discord, |r| {
-                r.kind(serenity::InteractionResponseType::ChannelMessageWithSource)
-                    .interaction_response_data(|f| {
-                        send_as_initial_response(data, allowed_mentions, f);
-                        f
-                    })
-            })
-            .await?;
-        ctx.has_sent_initial_response
-            .store(true, std::sync::atomic::Ordering::SeqCst);
-    }
-
-    Ok(())
+
+        // With reuse_response, edit the latest followup if there is one, or else the initial
+        // response
+        let last_followup_id = self.last_followup_id.and_then(|x| *x.lock().unwrap());
+        match (reuse_response, last_followup_id) {
+            (true, Some(followup_id)) => {
+                let request = crate::MessageRequest::EditFollowup {
+                    interaction: self.interaction,
+                    message_id: followup_id,
+                    reply: data,
+                };
+                let followup = self.framework.send_message(self.discord, request).await?;
+                Ok(Some(Box::new(followup)))
+            }
+            (true, None) => {
+                let request = crate::ResponseRequest::EditInitialResponse {
+                    interaction: self.interaction,
+                    reply: data,
+                };
+                self.framework.send_response(self.discord, request).await?;
+                Ok(None)
+            }
+            (false, _) => {
+                let request = crate::MessageRequest::CreateFollowup {
+                    interaction: self.interaction,
+                    reply: data,
+                };
+                let followup = self.framework.send_message(self.discord, request).await?;
+                if let Some(last_followup_id) = self.last_followup_id {
+                    *last_followup_id.lock().unwrap() = Some(followup.id);
+                }
+                Ok(Some(Box::new(followup)))
+            }
+        }
+    }
+
+    /// Acknowledges the interaction with a "thinking" message, which is replaced by the first
+    /// followup message. Does nothing if the interaction was already responded to
+    pub(crate) async fn defer_new_message(self, ephemeral: bool) -> Result<(), serenity::Error> {
+        if self.claim_initial_response() {
+            let response = crate::InitialResponse::DeferredMessage { ephemeral };
+            self.send_initial_response(response).await?;
+        }
+        Ok(())
+    }
+
+    /// Marks the initial response as sent, before it's actually sent. Returns false if it was
+    /// already claimed.
+    ///
+    /// Claiming up front keeps concurrent senders, like the automatic defer while the command is
+    /// still running, from sending a second initial response while the first one is in flight
+    pub(crate) fn claim_initial_response(self) -> bool {
+        self.has_sent_initial_response
+            .compare_exchange(
+                false,
+                true,
+                std::sync::atomic::Ordering::SeqCst,
+                std::sync::atomic::Ordering::SeqCst,
+            )
+            .is_ok()
+    }
+
+    /// Sends the initial response, which must have been claimed with
+    /// [`Self::claim_initial_response`]. If sending fails, the claim is released again
+    pub(crate) async fn send_initial_response(
+        self,
+        response: crate::InitialResponse<'_>,
+    ) -> Result<(), serenity::Error> {
+        let request = crate::ResponseRequest::InitialResponse {
+            interaction: self.interaction,
+            response,
+        };
+        let result = self.framework.send_response(self.discord, request).await;
+        if result.is_err() {
+            self.has_sent_initial_response
+                .store(false, std::sync::atomic::Ordering::SeqCst);
+        }
+        result
+    }
 }
@@ -64,6 +64,14 @@ impl<'a> ApplicationCommandOrAutocompleteInteraction<'a> {
             Self::Autocomplete(x) => &x.user,
         }
     }
+
+    /// Returns the selected language of the invoking user, e.g. `en-US` or `ja`
+    pub fn locale(self) -> &'a str {
+        match self {
+            Self::ApplicationCommand(x) => &x.locale,
+            Self::Autocomplete(x) => &x.locale,
+        }
+    }
 }
 
 /// Application command specific context passed to command invocations.
@@ -72,10 +80,14 @@ pub struct ApplicationContext<'a, U, E> {
     pub discord: &'a serenity::Context,
     /// The interaction which triggered this command execution.
     pub interaction: ApplicationCommandOrAutocompleteInteraction<'a>,
-    /// Keeps track of whether an initial response has been sent.
+    /// Keeps track of whether an initial response has been sent. Set as soon as the response is
+    /// claimed, before its request completes.
     ///
     /// Discord requires different HTTP endpoints for initial and additional responses.
     pub has_sent_initial_response: &'a std::sync::atomic::AtomicBool,
+    /// ID of the most recent followup message, if any. Used to edit it instead of sending a new
+    /// followup if [`crate::Command::reuse_response`] is set
+    pub last_followup_id: &'a std::sync::Mutex<Option<serenity::MessageId>>,
     /// Read-only reference to the framework
     ///
     /// Useful if you need the list of commands, for example for a custom help command
@@ -102,6 +114,7 @@ impl<U: std::fmt::Debug, E: std::fmt::Debug> std::fmt::Debug for ApplicationCont
             discord: _,
             interaction,
             has_sent_initial_response,
+            last_followup_id,
             framework: _,
             command: _,
             data,
@@ -111,6 +124,7 @@ impl<U: std::fmt::Debug, E: std::fmt::Debug> std::fmt::Debug for ApplicationCont
             .field("discord", &"<serenity Context>")
             .field("interaction", interaction)
             .field("has_sent_initial_response", has_sent_initial_response)
+            .field("last_followup_id", last_followup_id)
             .field("framework", &"<poise Framework>")
             .field("command", &"<poise Command>")
             .field("data", data)
@@ -118,7 +132,7 @@ impl<U: std::fmt::Debug, E: std::fmt::Debug> std::fmt::Debug for ApplicationCont
     }
 }
 
-impl<U, E> ApplicationContext<'_, U, E> {
+impl<'a, U, E> ApplicationContext<'a, U, E> {
     /// See [`crate::Context::defer()`]
     pub async fn defer_response(&self, ephemeral: bool) -> Result<(), serenity::Error> {
         let interaction = match self.interaction {
@@ -126,20 +140,24 @@ impl<U, E> ApplicationContext<'_, U, E> {
             ApplicationCommandOrAutocompleteInteraction::Autocomplete(_) => return Ok(()),
         };
 
-        let mut flags = serenity::InteractionApplicationCommandCallbackDataFlags::empty();
-        if ephemeral {
-            flags |= serenity::InteractionApplicationCommandCallbackDataFlags::EPHEMERAL;
-        }
+        self.responder(interaction)
+            .defer_new_message(ephemeral)
+            .await
+    }
 
-        interaction
-            .create_interaction_response(self.discord, |f| {
-                f.kind(serenity::InteractionResponseType::DeferredChannelMessageWithSource)
-                    .interaction_response_data(|f| f.flags(flags))
-            })
-            .await?;
-        self.has_sent_initial_response
-            .store(true, std::sync::atomic::Ordering::SeqCst);
-        Ok(())
+    /// Returns the [`crate::InteractionResponder`] that sends responses to the given interaction,
+    /// which must be this context's interaction
+    pub(crate) fn responder(
+        &self,
+        interaction: &'a serenity::ApplicationCommandInteraction,
+    ) -> crate::InteractionResponder<'a, U, E> {
+        crate::InteractionResponder {
+            framework: self.framework,
+            discord: self.discord,
+            interaction: crate::InteractionRef::Command(interaction),
+            has_sent_initial_response: self.has_sent_initial_response,
+            last_followup_id: Some(self.last_followup_id),
+        }
     }
 }
 
@@ -181,10 +199,21 @@ impl<U, E> std::fmt::Debug for ContextMenuCommandAction<U, E> {
 pub struct CommandParameter<U, E> {
     /// Name of this command parameter
     pub name: &'static str,
+    /// Localized names of this parameter, keyed by locale (e.g. `ja`), for slash commands
+    pub name_localizations: std::collections::HashMap<&'static str, &'static str>,
     /// Description of the command. Required for slash commands
     pub description: Option<&'static str>,
+    /// Localized descriptions of this parameter, keyed by locale (e.g. `ja`), for slash commands
+    pub description_localizations: std::collections::HashMap<&'static str, &'static str>,
     /// `true` is this parameter is required, `false` if it's optional or variadic
     pub required: bool,
+    /// `true` if this parameter accepts any number of values (`Vec<T>`)
+    pub variadic: bool,
+    /// `true` if this parameter consumes the entire rest of the message (`#[rest]`, prefix-only)
+    pub rest: bool,
+    /// `true` if this parameter is a flag which is set by typing the parameter name literally
+    /// (`#[flag]`, prefix-only)
+    pub flag: bool,
     /// If this parameter is a channel, users can only enter these channel types in a slash command
     ///
     /// Prefix commands are currently unaffected by this
@@ -214,6 +243,26 @@ pub struct CommandParameter<U, E> {
 }
 
 impl<U, E> CommandParameter<U, E> {
+    /// Returns how this parameter is displayed in a usage signature, e.g. `<user>` if it's
+    /// required, `[reason...]` if it's optional and consumes the rest of the message, or
+    /// `[flag: silent]` for flags
+    pub fn usage(&self) -> String {
+        if self.flag {
+            return format!("[flag: {}]", self.name);
+        }
+
+        let ellipsis = if self.rest || self.variadic {
+            "..."
+        } else {
+            ""
+        };
+        if self.required {
+            format!("<{}{}>", self.name, ellipsis)
+        } else {
+            format!("[{}{}]", self.name, ellipsis)
+        }
+    }
+
     /// Generates a slash command parameter builder from this [`CommandParameter`] instance. This
     /// can be used to register the command on Discord's servers
     pub fn create_as_slash_command_option(
@@ -225,6 +274,18 @@ impl<U, E> CommandParameter<U, E> {
             .name(self.name)
             .description(self.description?)
             .set_autocomplete(self.autocomplete_callback.is_some());
+        if !self.name_localizations.is_empty() {
+            builder.0.insert(
+                "name_localizations",
+                serenity::json::json!(self.name_localizations),
+            );
+        }
+        if !self.description_localizations.is_empty() {
+            builder.0.insert(
+                "description_localizations",
+                serenity::json::json!(self.description_localizations),
+            );
+        }
         if let Some(channel_types) = &self.channel_types {
             builder.channel_types(channel_types);
         }
@@ -237,8 +298,13 @@ impl<U, E> std::fmt::Debug for CommandParameter<U, E> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let Self {
             name,
+            name_localizations,
             description,
+            description_localizations,
             required,
+            variadic,
+            rest,
+            flag,
             channel_types,
             type_setter,
             autocomplete_callback,
@@ -246,8 +312,13 @@ impl<U, E> std::fmt::Debug for CommandParameter<U, E> {
 
         f.debug_struct("CommandParameter")
             .field("name", name)
+            .field("name_localizations", name_localizations)
             .field("description", description)
+            .field("description_localizations", description_localizations)
             .field("required", required)
+            .field("variadic", variadic)
+            .field("rest", rest)
+            .field("flag", flag)
             .field("channel_types", channel_types)
             .field("type_setter", &type_setter.map(|f| f as *const ()))
             .field(
@@ -31,6 +31,9 @@ pub struct Command<U, E> {
     pub subcommands: Vec<Command<U, E>>,
     /// Main name of the command. Aliases (prefix-only) can be set in [`Self::aliases`].
     pub name: &'static str,
+    /// Localized names of the command, keyed by locale (e.g. `ja`). Used when registering the
+    /// command on Discord and in help menus of application commands
+    pub name_localizations: std::collections::HashMap<&'static str, &'static str>,
     /// Full name including parent command names.
     ///
     /// Initially set to just [`Self::name`] and properly populated when the framework is started.
@@ -47,15 +50,21 @@ pub struct Command<U, E> {
     pub hide_in_help: bool,
     /// Short description of the command. Displayed inline in help menus and similar.
     pub inline_help: Option<&'static str>,
+    /// Localized versions of [`Self::inline_help`], keyed by locale (e.g. `ja`). Used as the
+    /// slash command description and in help menus of application commands
+    pub description_localizations: std::collections::HashMap<&'static str, &'static str>,
     /// Multiline description with detailed usage instructions. Displayed in the command specific
     /// help: `~help command_name`
     // TODO: fix the inconsistency that this is String and everywhere else it's &'static str
     pub multiline_help: Option<fn() -> String>,
     /// Handles command cooldowns. Mainly for framework internal use
-    pub cooldowns: std::sync::Mutex<crate::Cooldowns>,
+    pub cooldowns: crate::Cooldowns,
     /// After the first response, whether to post subsequent responses as edits to the initial
     /// message
     ///
+    /// In application commands, the most recent followup message is edited if there is one, or
+    /// else the initial response.
+    ///
     /// Note: in prefix commands, this only has an effect if
     /// `crate::PrefixFrameworkOptions::edit_tracker` is set.
     pub reuse_response: bool,
@@ -72,6 +81,9 @@ pub struct Command<U, E> {
     /// If true, only users from the [owners list](crate::FrameworkOptions::owners) may use this
     /// command.
     pub owners_only: bool,
+    /// If true, the command can only be invoked by users who are connected to a voice channel in
+    /// the guild, see [`crate::Context::author_voice_channel`]
+    pub requires_voice: bool,
     /// Command-specific override for [`crate::FrameworkOptions::on_error`]
     pub on_error: Option<fn(crate::FrameworkError<'_, U, E>) -> BoxFuture<'_, ()>>,
     /// If this function returns false, this command will not be executed.
@@ -94,6 +106,9 @@ pub struct Command<U, E> {
     pub context_menu_name: Option<&'static str>,
     /// Whether responses to this command should be ephemeral by default (application-only)
     pub ephemeral: bool,
+    /// Whether to defer the response automatically if the command doesn't respond quickly enough
+    /// (application-only). If None, [`crate::FrameworkOptions::auto_defer`] is used
+    pub auto_defer: Option<bool>,
 }
 
 impl<U, E> PartialEq for Command<U, E> {
@@ -111,16 +126,19 @@ impl<U, E> std::fmt::Debug for Command<U, E> {
             context_menu_action,
             subcommands,
             name,
+            name_localizations,
             qualified_name,
             identifying_name,
             category,
             hide_in_help,
             inline_help,
+            description_localizations,
             multiline_help,
             cooldowns,
             required_permissions,
             required_bot_permissions,
             owners_only,
+            requires_voice,
             on_error,
             check,
             parameters,
@@ -130,6 +148,7 @@ impl<U, E> std::fmt::Debug for Command<U, E> {
             broadcast_typing,
             context_menu_name,
             ephemeral,
+            auto_defer,
         } = self;
 
         f.debug_struct("Command")
@@ -138,16 +157,19 @@ impl<U, E> std::fmt::Debug for Command<U, E> {
             .field("context_menu_action", context_menu_action)
             .field("subcommands", subcommands)
             .field("name", name)
+            .field("name_localizations", name_localizations)
             .field("qualified_name", qualified_name)
             .field("identifying_name", identifying_name)
             .field("category", category)
             .field("hide_in_help", hide_in_help)
             .field("inline_help", inline_help)
+            .field("description_localizations", description_localizations)
             .field("multiline_help", multiline_help)
             .field("cooldowns", cooldowns)
             .field("required_permissions", required_permissions)
             .field("required_bot_permissions", required_bot_permissions)
             .field("owners_only", owners_only)
+            .field("requires_voice", requires_voice)
             .field("on_error", &on_error.map(|f| f as *const ()))
             .field("check", &check.map(|f| f as *const ()))
             .field("parameters", parameters)
@@ -157,11 +179,76 @@ impl<U, E> std::fmt::Debug for Command<U, E> {
             .field("broadcast_typing", broadcast_typing)
             .field("context_menu_name", context_menu_name)
             .field("ephemeral", ephemeral)
+            .field("auto_defer", auto_defer)
             .finish()
     }
 }
 
 impl<U, E> Command<U, E> {
+    /// Returns the name of this command in the given locale, falling back to [`Self::name`] if
+    /// there's no translation (or no locale)
+    pub fn localized_name(&self, locale: Option<&str>) -> &'static str {
+        locale
+            .and_then(|locale| self.name_localizations.get(locale))
+            .copied()
+            .unwrap_or(self.name)
+    }
+
+    /// Returns the description of this command in the given locale, falling back to
+    /// [`Self::inline_help`] if there's no translation (or no locale)
+    pub fn localized_inline_help(&self, locale: Option<&str>) -> Option<&'static str> {
+        locale
+            .and_then(|locale| self.description_localizations.get(locale))
+            .copied()
+            .or(self.inline_help)
+    }
+
+    /// Adds [`Self::name_localizations`] and [`Self::description_localizations`] to the given
+    /// application command (option) builder data
+    fn insert_localizations(
+        &self,
+        builder: &mut std::collections::HashMap<&'static str, serenity::json::Value>,
+    ) {
+        if !self.name_localizations.is_empty() {
+            builder.insert(
+                "name_localizations",
+                serenity::json::json!(self.name_localizations),
+            );
+        }
+        if !self.description_localizations.is_empty() {
+            builder.insert(
+                "description_localizations",
+                serenity::json::json!(self.description_localizations),
+            );
+        }
+    }
+
+    /// Returns [`Self::parameters`] with optional parameters last, which is the order Discord
+    /// requires for slash command options
+    fn slash_parameters(&self) -> Vec<&crate::CommandParameter<U, E>> {
+        let mut parameters = self.parameters.iter().collect::<Vec<_>>();
+        parameters.sort_by_key(|param| !param.required);
+        parameters
+    }
+
+    /// Generates a usage signature from the command's qualified name and parameters, for example
+    /// `~ban <user> [reason...]` when passed `~` as the prefix
+    ///
+    /// Required parameters are wrapped in angle brackets and optional parameters in square
+    /// brackets. Variadic and `#[rest]` parameters are marked with `...` and flags are displayed as
+    /// `[flag: name]`. Commands with subcommands but without parameters end in `<subcommand>`.
+    pub fn usage(&self, prefix: &str) -> String {
+        let mut usage = format!("{}{}", prefix, self.qualified_name);
+        for param in &self.parameters {
+            usage += " ";
+            usage += &param.usage();
+        }
+        if self.parameters.is_empty() && !self.subcommands.is_empty() {
+            usage += " <subcommand>";
+        }
+        usage
+    }
+
     /// Serializes this Command into an application command option, which is the form which Discord
     /// requires subcommands to be in
     fn create_as_subcommand(&self) -> Option<serenity::CreateApplicationCommandOption> {
@@ -171,11 +258,12 @@ impl<U, E> Command<U, E> {
         builder
             .name(self.name)
             .description(self.inline_help.unwrap_or("A slash command"));
+        self.insert_localizations(&mut builder.0);
 
         if self.subcommands.is_empty() {
             builder.kind(serenity::ApplicationCommandOptionType::SubCommand);
 
-            for param in &self.parameters {
+            for param in self.slash_parameters() {
                 // Using `?` because if this command has slash-incompatible parameters, we cannot
                 // just ignore them but have to abort the creation process entirely
                 builder.add_sub_option(param.create_as_slash_command_option()?);
@@ -202,9 +290,10 @@ impl<U, E> Command<U, E> {
         builder
             .name(self.name)
             .description(self.inline_help.unwrap_or("A slash command"));
+        self.insert_localizations(&mut builder.0);
 
         if self.subcommands.is_empty() {
-            for param in &self.parameters {
+            for param in self.slash_parameters() {
                 // Using `?` because if this command has slash-incompatible parameters, we cannot
                 // just ignore them but have to abort the creation process entirely
                 builder.add_option(param.create_as_slash_command_option()?);
@@ -234,6 +323,13 @@ impl<U, E> Command<U, E> {
                     serenity::ApplicationCommandType::Message
                 }
             });
+        // Context menu commands don't have descriptions
+        if !self.name_localizations.is_empty() {
+            builder.0.insert(
+                "name_localizations",
+                serenity::json::json!(self.name_localizations),
+            );
+        }
 
         Some(builder)
     }
@@ -256,3 +352,60 @@ impl<U, E> Command<U, E> {
         self
     }
 }
+
+#[test]
+fn test_usage() {
+    fn param(name: &'static str, required: bool) -> crate::CommandParameter<(), ()> {
+        crate::CommandParameter {
+            name,
+            name_localizations: Default::default(),
+            description: None,
+            description_localizations: Default::default(),
+            required,
+            variadic: false,
+            rest: false,
+            flag: false,
+            channel_types: None,
+            type_setter: None,
+            autocomplete_callback: None,
+        }
+    }
+
+    let ban = Command::<(), ()> {
+        qualified_name: "ban".into(),
+        parameters: vec![
+            param("user", true),
+            crate::CommandParameter {
+                flag: true,
+                ..param("silent", false)
+            },
+            crate::CommandParameter {
+                rest: true,
+                ..param("reason", false)
+            },
+        ],
+        ..Default::default()
+    };
+    assert_eq!(ban.usage("~"), "~ban <user> [flag: silent] [reason...]");
+
+    let config = Command::<(), ()> {
+        qualified_name: "config".into(),
+        subcommands: vec![Command {
+            qualified_name: "config set".into(),
+            parameters: vec![
+                param("key", true),
+                crate::CommandParameter {
+                    variadic: true,
+                    ..param("values", false)
+                },
+            ],
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    assert_eq!(config.usage("/"), "/config <subcommand>");
+    assert_eq!(
+        config.subcommands[0].usage("/"),
+        "/config set <key> [values...]"
+    );
+}
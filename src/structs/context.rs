@@ -33,7 +33,7 @@ impl<'a, U, E> Context<'a, U, E> {
     /// Also sets the [`crate::ApplicationContext::has_sent_initial_response`] flag so subsequent
     /// responses will be sent in the correct manner.
     ///
-    /// No-op if this is an autocomplete context
+    /// No-op if this is an autocomplete context, or if the interaction was already responded to
     ///
     /// This will make the response public; to make it ephemeral, use [`Self::defer_ephemeral()`].
     pub async fn defer(self) -> Result<(), serenity::Error> {
@@ -187,6 +187,17 @@ impl<'a, U, E> Context<'a, U, E> {
         }
     }
 
+    /// Returns the locale (selected language) of the invoking user, e.g. `en-US` or `ja`, which can
+    /// be used to pick translated responses
+    ///
+    /// Only application commands carry the user's locale; in prefix contexts, this returns None.
+    pub fn locale(&self) -> Option<&'a str> {
+        match self {
+            Self::Application(ctx) => Some(ctx.interaction.locale()),
+            Self::Prefix(_) => None,
+        }
+    }
+
     /// Returns the command name that this command was invoked with
     ///
     /// Mainly useful in prefix context, for example to check whether a command alias was used.
@@ -199,6 +210,24 @@ impl<'a, U, E> Context<'a, U, E> {
             Self::Application(ctx) => &ctx.interaction.data().name,
         }
     }
+
+    /// Refunds the cooldown that this invocation started, so that it doesn't count towards the
+    /// command's cooldowns. Useful if the command failed or was cancelled.
+    ///
+    /// Does nothing if the framework didn't start a cooldown for this invocation (e.g. because it
+    /// was exempted by [`crate::FrameworkOptions::cooldown_exemption`]), if it was already
+    /// refunded, or if the invocation is more than an hour old.
+    pub async fn refund_cooldown(self) {
+        if self.framework().take_started_cooldown(self.id()) {
+            self.command().cooldowns.refund_cooldown(self).await;
+        }
+    }
+
+    /// Resets all cooldowns of this command that apply to this invocation, e.g. the cooldown of
+    /// the invoking user, as if the command had never been used
+    pub async fn reset_cooldown(self) {
+        self.command().cooldowns.reset_cooldown(self).await;
+    }
 }
 
 /// Trimmed down, more general version of [`Context`]
@@ -217,6 +246,28 @@ pub struct PartialContext<'a, U, E> {
     pub data: &'a U,
 }
 
+impl<U: std::fmt::Debug, E> std::fmt::Debug for PartialContext<'_, U, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self {
+            guild_id,
+            channel_id,
+            author,
+            discord: _,
+            framework: _,
+            data,
+        } = self;
+
+        f.debug_struct("PartialContext")
+            .field("guild_id", guild_id)
+            .field("channel_id", channel_id)
+            .field("author", author)
+            .field("discord", &"<serenity Context>")
+            .field("framework", &"<poise Framework>")
+            .field("data", data)
+            .finish()
+    }
+}
+
 impl<'a, U, E> From<Context<'a, U, E>> for PartialContext<'a, U, E> {
     fn from(ctx: Context<'a, U, E>) -> Self {
         Self {
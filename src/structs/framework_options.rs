@@ -17,6 +17,15 @@ pub struct FrameworkOptions<U, E> {
     ///
     /// If individual commands add their own check, both callbacks are run and must return true.
     pub command_check: Option<fn(crate::Context<'_, U, E>) -> BoxFuture<'_, Result<bool, E>>>,
+    /// Whether to automatically defer the response of application commands which haven't
+    /// responded within [`Self::auto_defer_threshold`], so that Discord doesn't show the
+    /// interaction as failed. Subsequent responses are sent as followups to the deferred response.
+    ///
+    /// Can be overridden per command with [`crate::Command::auto_defer`]
+    pub auto_defer: bool,
+    /// How long an application command may run without responding before it's deferred, see
+    /// [`Self::auto_defer`]. Discord requires a response within three seconds
+    pub auto_defer_threshold: std::time::Duration,
     /// Default set of allowed mentions to use for all responses
     pub allowed_mentions: Option<serenity::CreateAllowedMentions>,
     /// Called on every Discord event. Can be used to react to non-command events, like messages
@@ -34,6 +43,49 @@ pub struct FrameworkOptions<U, E> {
     /// If using [`crate::FrameworkBuilder`], automatically initialized with the bot application
     /// owner and team members
     pub owners: std::collections::HashSet<serenity::UserId>,
+    /// Called before checking command cooldowns. If it returns true, the invocation bypasses all
+    /// cooldowns and doesn't count towards them either.
+    ///
+    /// ```rust
+    /// # type Error = Box<dyn std::error::Error + Send + Sync>;
+    /// # use poise::serenity_prelude as serenity;
+    /// const MODERATOR_ROLE: serenity::RoleId = serenity::RoleId(123456789);
+    ///
+    /// let options = poise::FrameworkOptions::<(), Error> {
+    ///     // Bot owners and moderators don't have cooldowns
+    ///     cooldown_exemption: Some(|ctx| Box::pin(async move {
+    ///         if ctx.framework().options().owners.contains(&ctx.author().id) {
+    ///             return true;
+    ///         }
+    ///         match ctx.guild_id() {
+    ///             Some(guild_id) => ctx
+    ///                 .author()
+    ///                 .has_role(ctx.discord(), guild_id, MODERATOR_ROLE)
+    ///                 .await
+    ///                 .unwrap_or(false),
+    ///             None => false,
+    ///         }
+    ///     })),
+    ///     ..Default::default()
+    /// };
+    /// ```
+    pub cooldown_exemption: Option<fn(crate::Context<'_, U, E>) -> BoxFuture<'_, bool>>,
+    /// Where command cooldowns are stored. Defaults to [`crate::InMemoryCooldownStore`]; supply
+    /// your own [`crate::CooldownStore`] to keep cooldowns across restarts
+    pub cooldown_store: Box<dyn crate::CooldownStore>,
+    /// Handlers for message component interactions (buttons and select menus), matched by
+    /// `custom_id` prefix. See [`crate::ComponentHandler`]
+    pub component_handlers: Vec<crate::ComponentHandler<U, E>>,
+    /// How long the bot stays in a voice channel after its music queue has run empty, before it
+    /// disconnects. None to stay connected indefinitely. Only used with the `voice` feature
+    ///
+    /// Set to five minutes by default
+    pub music_idle_timeout: Option<std::time::Duration>,
+    /// If set, the bot leaves its voice channel after being alone there (apart from other bots)
+    /// for this long. Only used with the `voice` feature
+    ///
+    /// See [`crate::voice_presence`] for how the bot's voice channel is looked up
+    pub voice_auto_leave: Option<std::time::Duration>,
 }
 
 impl<U, E> FrameworkOptions<U, E> {
@@ -57,10 +109,17 @@ impl<U: std::fmt::Debug, E: std::fmt::Debug> std::fmt::Debug for FrameworkOption
             pre_command,
             post_command,
             command_check,
+            auto_defer,
+            auto_defer_threshold,
             allowed_mentions,
             listener,
             prefix_options,
             owners,
+            cooldown_exemption,
+            cooldown_store: _,
+            component_handlers,
+            music_idle_timeout,
+            voice_auto_leave,
         } = self;
 
         f.debug_struct("FrameworkOptions")
@@ -69,10 +128,20 @@ impl<U: std::fmt::Debug, E: std::fmt::Debug> std::fmt::Debug for FrameworkOption
             .field("pre_command", &(*pre_command as *const ()))
             .field("post_command", &(*post_command as *const ()))
             .field("command_check", &command_check.map(|f| f as *const ()))
+            .field("auto_defer", auto_defer)
+            .field("auto_defer_threshold", auto_defer_threshold)
             .field("allowed_mentions", allowed_mentions)
             .field("listener", &(*listener as *const ()))
             .field("prefix_options", prefix_options)
             .field("owners", owners)
+            .field(
+                "cooldown_exemption",
+                &cooldown_exemption.map(|f| f as *const ()),
+            )
+            .field("cooldown_store", &"< dyn CooldownStore >")
+            .field("component_handlers", component_handlers)
+            .field("music_idle_timeout", music_idle_timeout)
+            .field("voice_auto_leave", voice_auto_leave)
             .finish()
     }
 }
@@ -88,7 +157,7 @@ where
             on_error: |error| {
                 Box::pin(async move {
                     if let Err(e) = crate::builtins::on_error(error).await {
-                        println!("Error while handling error: {}", e);
+                        tracing::error!(error = %e, "error while handling error");
                     }
                 })
             },
@@ -96,6 +165,8 @@ where
             pre_command: |_| Box::pin(async {}),
             post_command: |_| Box::pin(async {}),
             command_check: None,
+            auto_defer: false,
+            auto_defer_threshold: std::time::Duration::from_secs(2),
             allowed_mentions: Some({
                 let mut f = serenity::CreateAllowedMentions::default();
                 // Only support direct user pings by default
@@ -104,6 +175,11 @@ where
             }),
             prefix_options: Default::default(),
             owners: Default::default(),
+            cooldown_exemption: None,
+            cooldown_store: Box::new(crate::InMemoryCooldownStore::new()),
+            component_handlers: Vec::new(),
+            music_idle_timeout: Some(std::time::Duration::from_secs(5 * 60)),
+            voice_auto_leave: None,
         }
     }
 }
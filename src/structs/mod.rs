@@ -33,6 +33,14 @@ pub enum FrameworkError<'a, U, E> {
         /// Error which was thrown in the setup code
         error: E,
     },
+    /// Automatic application command registration on startup failed, see
+    /// [`crate::FrameworkBuilder::register_commands`]
+    CommandRegistration {
+        /// Error which was returned by Discord
+        error: serenity::Error,
+        /// The guild the commands were registered in, or None if they were registered globally
+        guild_id: Option<serenity::GuildId>,
+    },
     /// User code threw an error in generic event listener
     Listener {
         /// Error which was thrown in the listener code
@@ -71,6 +79,12 @@ pub enum FrameworkError<'a, U, E> {
     CooldownHit {
         /// Time until the command may be invoked for the next time in the given context
         remaining_cooldown: std::time::Duration,
+        /// The cooldown bucket which was hit, e.g. the per-user bucket of the invoking user
+        bucket: crate::CooldownBucket,
+        /// Number of uses left in the hit bucket
+        remaining_uses: u32,
+        /// Number of uses the hit bucket allows in quick succession when it's fully replenished
+        capacity: u32,
         /// General context
         ctx: Context<'a, U, E>,
     },
@@ -96,6 +110,26 @@ pub enum FrameworkError<'a, U, E> {
         /// General context
         ctx: Context<'a, U, E>,
     },
+    /// A command with [`crate::Command::requires_voice`] was invoked by a user who isn't connected
+    /// to a voice channel in this guild
+    NotInVoiceChannel {
+        /// General context
+        ctx: Context<'a, U, E>,
+    },
+    /// A message started with a prefix but didn't match any command. Only raised if
+    /// [`crate::PrefixFrameworkOptions::suggest_similar_commands`] is enabled
+    UnknownCommand {
+        /// The message that contained the unknown command
+        msg: &'a serenity::Message,
+        /// The prefix that the message was invoked with
+        prefix: &'a str,
+        /// The message content after the prefix, starting with the unknown command name
+        msg_content: &'a str,
+        /// Qualified names of similarly named commands, most similar first. May be empty
+        suggestions: Vec<String>,
+        /// General context
+        ctx: crate::PartialContext<'a, U, E>,
+    },
     /// Provided pre-command check either errored, or returned false, so command execution aborted
     CommandCheckFailed {
         /// If execution wasn't aborted because of an error but because it successfully returned
@@ -104,4 +138,20 @@ pub enum FrameworkError<'a, U, E> {
         /// General context
         ctx: Context<'a, U, E>,
     },
+    /// User code threw an error in a [`crate::ComponentHandler`]
+    ComponentHandler {
+        /// Error which was thrown in the handler code
+        error: E,
+        /// Component handler context
+        ctx: crate::ComponentContext<'a, U, E>,
+    },
+    /// The check of a [`crate::ComponentHandler`] either errored, or returned false, so the
+    /// handler wasn't executed
+    ComponentCheckFailed {
+        /// If execution wasn't aborted because of an error but because it successfully returned
+        /// false, this field is None
+        error: Option<E>,
+        /// Component handler context
+        ctx: crate::ComponentContext<'a, U, E>,
+    },
 }
@@ -0,0 +1,583 @@
+/*!
+Tools for testing commands end to end, without a connection to Discord.
+
+[`TestHarness`] creates a [`crate::Framework`] from your [`crate::FrameworkOptions`] and lets you
+feed it synthetic messages, message edits and application command interactions. Instead of being
+sent to Discord, every response is recorded as a [`CapturedReply`], which you can then assert on.
+
+```rust
+# type Error = Box<dyn std::error::Error + Send + Sync>;
+# type Context<'a> = poise::Context<'a, (), Error>;
+/// Echoes the given text
+#[poise::command(prefix_command, slash_command, track_edits)]
+async fn echo(
+    ctx: Context<'_>,
+    #[description = "Text to echo"] #[rest] text: String,
+) -> Result<(), Error> {
+    ctx.say(text).await?;
+    Ok(())
+}
+
+# #[tokio::main] async fn main() {
+let harness = poise::testing::TestHarness::new(
+    poise::FrameworkOptions {
+        commands: vec![echo()],
+        prefix_options: poise::PrefixFrameworkOptions {
+            prefix: Some("~".into()),
+            edit_tracker: Some(poise::EditTracker::for_timespan(
+                std::time::Duration::from_secs(60),
+            )),
+            ..Default::default()
+        },
+        ..Default::default()
+    },
+    (),
+);
+
+let msg = harness.send_message("~echo hello").await;
+harness.edit_message(&msg, "~echo goodbye").await;
+harness.run_application_command("echo", poise::serenity::json::json!([
+    { "name": "text", "type": 3, "value": "hi there" },
+])).await;
+
+let replies = harness.take_replies();
+assert_eq!(replies[0].kind, poise::testing::CapturedReplyKind::Message);
+assert_eq!(replies[0].content.as_deref(), Some("hello"));
+assert_eq!(replies[1].kind, poise::testing::CapturedReplyKind::MessageEdit);
+assert_eq!(replies[1].content.as_deref(), Some("goodbye"));
+assert_eq!(replies[2].kind, poise::testing::CapturedReplyKind::InitialResponse);
+assert_eq!(replies[2].content.as_deref(), Some("hi there"));
+# }
+```
+*/
+
+use crate::serenity_prelude as serenity;
+#[allow(unused_imports)] // import is required if serenity simdjson feature is enabled
+use serenity::json::prelude::*;
+
+/// The way in which a [`CapturedReply`] would have been sent to Discord
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CapturedReplyKind {
+    /// A new message in the channel of a prefix command
+    Message,
+    /// An edit of a previously sent prefix command response, e.g. because of edit tracking
+    MessageEdit,
+    /// The initial response to an application command interaction
+    InitialResponse,
+    /// The initial response was deferred (see [`crate::Context::defer`])
+    DeferredResponse,
+    /// An edit of the initial interaction response. After a component interaction was deferred
+    /// with [`crate::ComponentContext::defer`], the initial response is the component's message
+    InitialResponseEdit,
+    /// A followup message to an application command or component interaction
+    Followup,
+    /// An edit of a followup message (see [`crate::ReplyHandle::edit`])
+    FollowupEdit,
+    /// A previously sent response was deleted (see [`crate::ReplyHandle::delete`])
+    Deletion,
+    /// An edit of the message containing the used component, in response to a component
+    /// interaction (see [`crate::ComponentContext::update_message`])
+    ComponentMessageUpdate,
+    /// A modal was opened in response to an interaction (see [`crate::execute_modal`])
+    Modal,
+}
+
+/// A response which the framework would have sent to Discord, recorded by [`TestHarness`]
+#[derive(Clone, Debug)]
+pub struct CapturedReply {
+    /// How the response would have been sent
+    pub kind: CapturedReplyKind,
+    /// Channel the response would have been sent in
+    pub channel_id: serenity::ChannelId,
+    /// ID of the sent or edited message, if the response created or edited a regular message
+    pub message_id: Option<serenity::MessageId>,
+    /// Message content
+    pub content: Option<String>,
+    /// Embeds, if present
+    pub embeds: Vec<serenity::CreateEmbed>,
+    /// Number of attachments
+    pub num_attachments: usize,
+    /// Whether the response was ephemeral
+    pub ephemeral: bool,
+    /// Message components, if present
+    pub components: Option<serenity::CreateComponents>,
+}
+
+impl CapturedReply {
+    /// Converts this response into the message which Discord would have created from it, authored
+    /// by the bot user
+    pub(crate) fn to_message(&self, discord: &serenity::Context) -> serenity::Message {
+        let mut author = serenity::User::default();
+        author.id = discord.cache.current_user_id();
+        author.bot = true;
+
+        let mut msg = serenity::CustomMessage::new().build();
+        msg.id = self.message_id.unwrap_or_default();
+        msg.channel_id = self.channel_id;
+        msg.author = author;
+        msg.content = self.content.clone().unwrap_or_default();
+        msg
+    }
+}
+
+/// Collects all responses of a framework that was created by [`TestHarness`]
+#[derive(Debug)]
+struct ReplyRecorder {
+    /// All responses so far, in the order they were sent
+    replies: std::sync::Mutex<Vec<CapturedReply>>,
+    /// Used to hand out unique message IDs
+    next_id: std::sync::atomic::AtomicU64,
+}
+
+impl Default for ReplyRecorder {
+    fn default() -> Self {
+        Self {
+            replies: std::sync::Mutex::new(Vec::new()),
+            next_id: std::sync::atomic::AtomicU64::new(1 << 32),
+        }
+    }
+}
+
+impl ReplyRecorder {
+    /// Returns a new unique snowflake
+    fn next_id(&self) -> u64 {
+        self.next_id
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst)
+    }
+
+    /// Records a response. If `message_id` is None and the response creates a new message, a new
+    /// message ID is allocated
+    fn record(
+        &self,
+        kind: CapturedReplyKind,
+        channel_id: serenity::ChannelId,
+        message_id: Option<serenity::MessageId>,
+        reply: crate::CreateReply<'_>,
+    ) -> CapturedReply {
+        let message_id = match kind {
+            CapturedReplyKind::Message | CapturedReplyKind::Followup => {
+                Some(message_id.unwrap_or_else(|| serenity::MessageId(self.next_id())))
+            }
+            _ => message_id,
+        };
+
+        let crate::CreateReply {
+            content,
+            embeds,
+            attachments,
+            ephemeral,
+            components,
+            overflow: _,
+        } = reply;
+
+        let captured = CapturedReply {
+            kind,
+            channel_id,
+            message_id,
+            content,
+            embeds,
+            num_attachments: attachments.len(),
+            ephemeral,
+            components,
+        };
+        self.replies.lock().unwrap().push(captured.clone());
+        captured
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::ResponseSink for ReplyRecorder {
+    async fn send_message(
+        &self,
+        discord: &serenity::Context,
+        _: Option<&serenity::CreateAllowedMentions>,
+        request: crate::MessageRequest<'_, '_>,
+    ) -> Result<serenity::Message, serenity::Error> {
+        let captured = match request {
+            crate::MessageRequest::CreateMessage { channel_id, reply } => {
+                self.record(CapturedReplyKind::Message, channel_id, None, reply)
+            }
+            crate::MessageRequest::EditMessage {
+                channel_id,
+                message_id,
+                reply,
+            } => self.record(
+                CapturedReplyKind::MessageEdit,
+                channel_id,
+                Some(message_id),
+                reply,
+            ),
+            crate::MessageRequest::UpdateMessage {
+                channel_id,
+                message_id,
+                reply,
+            } => self.record(
+                CapturedReplyKind::ComponentMessageUpdate,
+                channel_id,
+                Some(message_id),
+                reply,
+            ),
+            crate::MessageRequest::CreateFollowup { interaction, reply } => self.record(
+                CapturedReplyKind::Followup,
+                interaction.channel_id(),
+                None,
+                reply,
+            ),
+            crate::MessageRequest::EditFollowup {
+                interaction,
+                message_id,
+                reply,
+            } => self.record(
+                CapturedReplyKind::FollowupEdit,
+                interaction.channel_id(),
+                Some(message_id),
+                reply,
+            ),
+        };
+        Ok(captured.to_message(discord))
+    }
+
+    async fn send(
+        &self,
+        _: &serenity::Context,
+        _: Option<&serenity::CreateAllowedMentions>,
+        request: crate::ResponseRequest<'_, '_>,
+    ) -> Result<(), serenity::Error> {
+        match request {
+            crate::ResponseRequest::InitialResponse {
+                interaction,
+                response,
+            } => {
+                let channel_id = interaction.channel_id();
+                let (kind, message_id, reply) = match response {
+                    crate::InitialResponse::Message(reply) => {
+                        (CapturedReplyKind::InitialResponse, None, reply)
+                    }
+                    crate::InitialResponse::DeferredMessage { ephemeral } => {
+                        let reply = crate::CreateReply {
+                            ephemeral,
+                            ..Default::default()
+                        };
+                        (CapturedReplyKind::DeferredResponse, None, reply)
+                    }
+                    crate::InitialResponse::UpdateMessage(reply) => {
+                        let message_id = match interaction {
+                            crate::InteractionRef::Component(x) => Some(x.message.id),
+                            _ => None,
+                        };
+                        (CapturedReplyKind::ComponentMessageUpdate, message_id, reply)
+                    }
+                    crate::InitialResponse::DeferredUpdate => (
+                        CapturedReplyKind::DeferredResponse,
+                        None,
+                        Default::default(),
+                    ),
+                    crate::InitialResponse::Modal(_) => {
+                        (CapturedReplyKind::Modal, None, Default::default())
+                    }
+                };
+                self.record(kind, channel_id, message_id, reply);
+            }
+            crate::ResponseRequest::EditInitialResponse { interaction, reply } => {
+                self.record(
+                    CapturedReplyKind::InitialResponseEdit,
+                    interaction.channel_id(),
+                    None,
+                    reply,
+                );
+            }
+            crate::ResponseRequest::DeleteInitialResponse { interaction } => {
+                self.record(
+                    CapturedReplyKind::Deletion,
+                    interaction.channel_id(),
+                    None,
+                    Default::default(),
+                );
+            }
+            crate::ResponseRequest::DeleteFollowup {
+                interaction,
+                message_id,
+            } => {
+                self.record(
+                    CapturedReplyKind::Deletion,
+                    interaction.channel_id(),
+                    Some(message_id),
+                    Default::default(),
+                );
+            }
+            crate::ResponseRequest::DeleteMessage {
+                channel_id,
+                message_id,
+            } => {
+                self.record(
+                    CapturedReplyKind::Deletion,
+                    channel_id,
+                    Some(message_id),
+                    Default::default(),
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Drives a [`crate::Framework`] with synthetic events, without connecting to Discord.
+///
+/// All events are dispatched in a single DM channel by a configurable author (see
+/// [`Self::set_author`]). The bot user has the ID that an empty [`serenity::Cache`] reports.
+///
+/// See the [module docs](self) for an example.
+pub struct TestHarness<U, E> {
+    /// The offline framework
+    framework: crate::Framework<U, E>,
+    /// Records all responses of the framework
+    recorder: std::sync::Arc<ReplyRecorder>,
+    /// Dummy serenity context. Any HTTP requests made with it will fail
+    discord: serenity::Context,
+    /// Author of all generated messages and interactions
+    author: serenity::User,
+    /// Channel of all generated messages and interactions
+    channel_id: serenity::ChannelId,
+    /// Used to hand out unique IDs for generated messages and interactions
+    next_id: std::sync::atomic::AtomicU64,
+}
+
+impl<U, E> TestHarness<U, E>
+where
+    U: Send + Sync + 'static,
+    E: 'static,
+{
+    /// Creates an offline framework with the given options and user data
+    pub fn new(options: crate::FrameworkOptions<U, E>, user_data: U) -> Self {
+        let discord = serenity::Context {
+            data: std::sync::Arc::new(serenity::RwLock::new(serenity::TypeMap::new())),
+            shard: serenity::ShardMessenger::new(serenity::futures::channel::mpsc::unbounded().0),
+            shard_id: Default::default(),
+            http: Default::default(),
+            cache: Default::default(),
+        };
+
+        let mut author = serenity::User::default();
+        author.id = serenity::UserId(1);
+        author.bot = false;
+        author.name = String::from("tester");
+
+        let recorder = std::sync::Arc::new(ReplyRecorder::default());
+        Self {
+            framework: crate::Framework::new_offline(options, user_data, recorder.clone()),
+            recorder,
+            discord,
+            author,
+            channel_id: serenity::ChannelId(2),
+            next_id: std::sync::atomic::AtomicU64::new(1 << 40),
+        }
+    }
+
+    /// Returns the offline framework
+    pub fn framework(&self) -> &crate::Framework<U, E> {
+        &self.framework
+    }
+
+    /// Returns the dummy serenity context that is passed to the framework
+    pub fn discord(&self) -> &serenity::Context {
+        &self.discord
+    }
+
+    /// Returns the author of all generated messages and interactions
+    pub fn author(&self) -> &serenity::User {
+        &self.author
+    }
+
+    /// Changes the author of all subsequently generated messages and interactions
+    pub fn set_author(&mut self, author: serenity::User) {
+        self.author = author;
+    }
+
+    /// Returns a new unique snowflake
+    fn next_id(&self) -> u64 {
+        self.next_id
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst)
+    }
+
+    /// Feeds an arbitrary event into the framework, just like the Discord gateway would
+    pub async fn dispatch(&self, event: &crate::Event<'_>) {
+        crate::framework::dispatch::dispatch_event(&self.framework, self.discord.clone(), event)
+            .await;
+    }
+
+    /// Generates a message with the given content, sent by [`Self::author`]
+    pub fn message(&self, content: impl Into<String>) -> serenity::Message {
+        let mut msg = serenity::CustomMessage::new().build();
+        msg.id = serenity::MessageId(self.next_id());
+        msg.channel_id = self.channel_id;
+        msg.author = self.author.clone();
+        msg.content = content.into();
+        msg
+    }
+
+    /// Generates an update event which changes the content of the given message
+    pub fn message_update(
+        &self,
+        msg: &serenity::Message,
+        new_content: impl Into<String>,
+    ) -> serenity::MessageUpdateEvent {
+        from_value(serenity::json::json!({
+            "id": msg.id,
+            "channel_id": msg.channel_id,
+            "author": msg.author,
+            "content": new_content.into(),
+            "timestamp": msg.timestamp,
+            "edited_timestamp": serenity::Timestamp::now(),
+        }))
+        .expect("failed to generate message update event")
+    }
+
+    /// Generates a slash command interaction, invoked by [`Self::author`].
+    ///
+    /// `options` is the JSON array of options as Discord would send it, for example
+    /// `json!([{ "name": "user", "type": 6, "value": "1234" }])`
+    pub fn application_command(
+        &self,
+        name: &str,
+        options: serenity::json::Value,
+    ) -> serenity::ApplicationCommandInteraction {
+        from_value(serenity::json::json!({
+            "id": self.next_id().to_string(),
+            "application_id": "1",
+            "type": 2,
+            "data": {
+                "id": "1",
+                "name": name,
+                "type": 1,
+                "options": options,
+            },
+            "channel_id": self.channel_id,
+            "user": self.author,
+            "token": "",
+            "version": 1,
+            "locale": "en-US",
+        }))
+        .expect("failed to generate application command interaction")
+    }
+
+    /// Generates a button interaction with the given `custom_id`, invoked by [`Self::author`] on
+    /// the given message
+    pub fn component_interaction(
+        &self,
+        msg: &serenity::Message,
+        custom_id: &str,
+    ) -> serenity::MessageComponentInteraction {
+        from_value(serenity::json::json!({
+            "id": self.next_id().to_string(),
+            "application_id": "1",
+            "type": 3,
+            "data": {
+                "custom_id": custom_id,
+                "component_type": 2,
+            },
+            "message": msg,
+            "channel_id": self.channel_id,
+            "user": self.author,
+            "token": "",
+            "version": 1,
+            "locale": "en-US",
+        }))
+        .expect("failed to generate component interaction")
+    }
+
+    /// Generates a modal submission with the given `custom_id`, invoked by [`Self::author`].
+    ///
+    /// `inputs` are the `custom_id`s and values of the modal's text inputs. The `custom_id` of a
+    /// modal opened with [`crate::ApplicationContext::execute_modal`] is the ID of the command
+    /// interaction
+    pub fn modal_submit(
+        &self,
+        custom_id: &str,
+        inputs: &[(&str, &str)],
+    ) -> serenity::ModalSubmitInteraction {
+        let rows = inputs
+            .iter()
+            .map(|(custom_id, value)| {
+                serenity::json::json!({
+                    "type": 1,
+                    "components": [{ "type": 4, "custom_id": custom_id, "value": value }],
+                })
+            })
+            .collect::<Vec<_>>();
+        from_value(serenity::json::json!({
+            "id": self.next_id().to_string(),
+            "application_id": "1",
+            "type": 5,
+            "data": {
+                "custom_id": custom_id,
+                "components": rows,
+            },
+            "channel_id": self.channel_id,
+            "user": self.author,
+            "token": "",
+            "version": 1,
+            "locale": "en-US",
+        }))
+        .expect("failed to generate modal submit interaction")
+    }
+
+    /// Generates a message with the given content and dispatches it. The message is returned so
+    /// that it can be edited with [`Self::edit_message`]
+    pub async fn send_message(&self, content: impl Into<String>) -> serenity::Message {
+        let new_message = self.message(content);
+        self.dispatch(&crate::Event::Message {
+            new_message: new_message.clone(),
+        })
+        .await;
+        new_message
+    }
+
+    /// Dispatches an edit of the given message
+    pub async fn edit_message(&self, msg: &serenity::Message, new_content: impl Into<String>) {
+        let event = self.message_update(msg, new_content);
+        self.dispatch(&crate::Event::MessageUpdate {
+            old_if_available: None,
+            new: None,
+            event,
+        })
+        .await;
+    }
+
+    /// Generates a slash command interaction and dispatches it. See [`Self::application_command`]
+    pub async fn run_application_command(&self, name: &str, options: serenity::json::Value) {
+        let interaction = self.application_command(name, options);
+        self.dispatch(&crate::Event::InteractionCreate {
+            interaction: serenity::Interaction::ApplicationCommand(interaction),
+        })
+        .await;
+    }
+
+    /// Generates a button interaction on the given message and dispatches it. See
+    /// [`Self::component_interaction`]
+    pub async fn click_component(&self, msg: &serenity::Message, custom_id: &str) {
+        let interaction = self.component_interaction(msg, custom_id);
+        self.dispatch(&crate::Event::InteractionCreate {
+            interaction: serenity::Interaction::MessageComponent(interaction),
+        })
+        .await;
+    }
+
+    /// Generates a modal submission and dispatches it. See [`Self::modal_submit`]
+    pub async fn submit_modal(&self, custom_id: &str, inputs: &[(&str, &str)]) {
+        let interaction = self.modal_submit(custom_id, inputs);
+        self.dispatch(&crate::Event::InteractionCreate {
+            interaction: serenity::Interaction::ModalSubmit(interaction),
+        })
+        .await;
+    }
+
+    /// Returns all responses recorded so far
+    pub fn replies(&self) -> Vec<CapturedReply> {
+        self.recorder.replies.lock().unwrap().clone()
+    }
+
+    /// Returns all responses recorded so far and clears the list
+    pub fn take_replies(&self) -> Vec<CapturedReply> {
+        std::mem::take(&mut *self.recorder.replies.lock().unwrap())
+    }
+}
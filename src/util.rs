@@ -16,22 +16,6 @@ impl<K: Eq, V> OrderedMap<K, V> {
         Self(Vec::new())
     }
 
-    /// Finds a value in the map by the given key
-    pub fn get(&self, k: &K) -> Option<&V> {
-        self.0
-            .iter()
-            .find(|entry| &entry.0 == k)
-            .map(|entry| &entry.1)
-    }
-
-    /// Inserts a key value pair into the map
-    pub fn insert(&mut self, k: K, v: V) {
-        match self.0.iter_mut().find(|entry| entry.0 == k) {
-            Some(entry) => entry.1 = v,
-            None => self.0.push((k, v)),
-        }
-    }
-
     /// Finds a value in the map by the given key, or inserts it if it doesn't exist
     pub fn get_or_insert_with(&mut self, k: K, v: impl FnOnce() -> V) -> &mut V {
         match self.0.iter().position(|entry| entry.0 == k) {
@@ -51,3 +35,44 @@ impl<K, V> IntoIterator for OrderedMap<K, V> {
         self.0.into_iter()
     }
 }
+
+/// Computes the edit distance between two strings, i.e. the minimum number of single character
+/// insertions, deletions, substitutions and transpositions of adjacent characters to turn one
+/// string into the other (optimal string alignment distance)
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+
+    // distances[i][j] is the distance between the first i chars of a and the first j chars of b
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            let mut distance = (distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1)
+                .min(distances[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+    distances[a.len()][b.len()]
+}
+
+#[test]
+fn test_edit_distance() {
+    assert_eq!(edit_distance("", ""), 0);
+    assert_eq!(edit_distance("help", "help"), 0);
+    assert_eq!(edit_distance("hlep", "help"), 1);
+    assert_eq!(edit_distance("hep", "help"), 1);
+    assert_eq!(edit_distance("kitten", "sitting"), 3);
+    assert_eq!(edit_distance("", "ban"), 3);
+}
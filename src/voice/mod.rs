@@ -0,0 +1,128 @@
+//! Voice channel helpers. Apart from the voice state lookups, these require the `voice` feature,
+//! which registers [songbird](https://docs.rs/songbird) on the client in [`crate::Framework::new`]
+
+mod presence;
+pub use presence::*;
+
+#[cfg(feature = "voice")]
+mod queue;
+#[cfg(feature = "voice")]
+pub use queue::*;
+
+use crate::serenity_prelude as serenity;
+
+/// Looks up the voice channel that the given user is connected to in the given guild, using the
+/// guild's voice states in the cache.
+///
+/// Returns None if the user isn't in a voice channel or if the guild isn't cached
+pub fn user_voice_channel(
+    discord: &serenity::Context,
+    guild_id: serenity::GuildId,
+    user_id: serenity::UserId,
+) -> Option<serenity::ChannelId> {
+    discord
+        .cache
+        .guild_field(guild_id, |guild| {
+            guild
+                .voice_states
+                .get(&user_id)
+                .and_then(|voice_state| voice_state.channel_id)
+        })
+        .flatten()
+}
+
+impl<U, E> crate::Context<'_, U, E> {
+    /// Returns the voice channel that the command author is connected to in this guild, as seen
+    /// by the cache.
+    ///
+    /// Returns None in DMs or if the author isn't in a voice channel
+    pub fn author_voice_channel(self) -> Option<serenity::ChannelId> {
+        user_voice_channel(self.discord(), self.guild_id()?, self.author().id)
+    }
+}
+
+#[cfg(feature = "voice")]
+impl<U, E> crate::Context<'_, U, E> {
+    /// Returns the songbird voice manager which was registered in [`crate::Framework::new`]
+    pub async fn songbird(self) -> std::sync::Arc<songbird::Songbird> {
+        songbird::get(self.discord())
+            .await
+            .expect("songbird is registered in Framework::new")
+    }
+
+    /// Joins the voice channel that the command author is connected to, or moves there if the bot
+    /// is already in another voice channel of this guild.
+    ///
+    /// Returns Ok(None) in DMs or if the author isn't in a voice channel
+    pub async fn join_author_channel(
+        self,
+    ) -> Result<
+        Option<std::sync::Arc<tokio::sync::Mutex<songbird::Call>>>,
+        songbird::error::JoinError,
+    > {
+        let (guild_id, channel_id) = match (self.guild_id(), self.author_voice_channel()) {
+            (Some(guild_id), Some(channel_id)) => (guild_id, channel_id),
+            _ => return Ok(None),
+        };
+
+        let (call, result) = self.songbird().await.join(guild_id, channel_id).await;
+        result?;
+        Ok(Some(call))
+    }
+
+    /// Disconnects from the voice channel in this guild and discards the songbird call.
+    ///
+    /// Fails with [`songbird::error::JoinError::NoCall`] in DMs or if the bot isn't in a voice
+    /// channel
+    pub async fn leave(self) -> Result<(), songbird::error::JoinError> {
+        let guild_id = match self.guild_id() {
+            Some(x) => x,
+            None => return Err(songbird::error::JoinError::NoCall),
+        };
+
+        self.songbird().await.remove(guild_id).await
+    }
+
+    /// Returns the music queue of this guild, creating it on first use. See [`crate::MusicQueue`]
+    ///
+    /// Returns None in DMs
+    pub async fn music_queue(self) -> Option<crate::MusicQueue> {
+        let guild_id = self.guild_id()?;
+        Some(self.framework().music_queue(self.discord(), guild_id).await)
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_requires_voice() {
+    let harness = crate::testing::TestHarness::new(
+        crate::FrameworkOptions {
+            commands: vec![crate::Command::<(), String> {
+                name: "play",
+                qualified_name: "play".into(),
+                requires_voice: true,
+                prefix_action: Some(|ctx, _| {
+                    Box::pin(async move {
+                        let _ = crate::say_reply(ctx.into(), "Playing").await;
+                        Ok(())
+                    })
+                }),
+                ..Default::default()
+            }],
+            prefix_options: crate::PrefixFrameworkOptions {
+                prefix: Some("~".into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        (),
+    );
+
+    harness.send_message("~play").await;
+    let replies = harness.take_replies();
+    assert_eq!(replies.len(), 1);
+    assert_eq!(
+        replies[0].content.as_deref(),
+        Some("You need to be in a voice channel to use this command")
+    );
+}
@@ -0,0 +1,158 @@
+//! Looks up the bot's voice channel from the cache, and leaves it after the bot has been alone
+//! there for a while, see [`crate::FrameworkOptions::voice_auto_leave`]
+
+use crate::serenity_prelude as serenity;
+
+/// The voice channel that the bot is connected to in a guild, see [`voice_presence`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct VoicePresence {
+    /// The voice channel the bot is connected to
+    pub channel_id: serenity::ChannelId,
+    /// How many users other than bots are connected to the same voice channel
+    pub member_count: usize,
+}
+
+/// Returns the voice channel that the bot is connected to in the given guild, and how many users
+/// other than bots are in there with it, using the guild's voice states in the cache.
+///
+/// Returns None if the bot isn't in a voice channel or if the guild isn't cached
+pub fn voice_presence(
+    discord: &serenity::Context,
+    guild_id: serenity::GuildId,
+) -> Option<VoicePresence> {
+    let bot_id = discord.cache.current_user_id();
+    discord
+        .cache
+        .guild_field(guild_id, |guild| {
+            presence_from_voice_states(&guild.voice_states, bot_id, |voice_state| {
+                // Voice states from voice state updates carry the member; the ones from guild
+                // create don't
+                let member = voice_state
+                    .member
+                    .as_ref()
+                    .or_else(|| guild.members.get(&voice_state.user_id));
+                match member {
+                    Some(member) => member.user.bot,
+                    None => {
+                        matches!(discord.cache.user(voice_state.user_id), Some(user) if user.bot)
+                    }
+                }
+            })
+        })
+        .flatten()
+}
+
+/// Finds the bot's voice channel in the given voice states of a guild and counts the users in
+/// there who aren't bots
+fn presence_from_voice_states(
+    voice_states: &std::collections::HashMap<serenity::UserId, serenity::VoiceState>,
+    bot_id: serenity::UserId,
+    is_bot: impl Fn(&serenity::VoiceState) -> bool,
+) -> Option<VoicePresence> {
+    let channel_id = voice_states.get(&bot_id)?.channel_id?;
+    let member_count = voice_states
+        .values()
+        .filter(|voice_state| {
+            voice_state.channel_id == Some(channel_id)
+                && voice_state.user_id != bot_id
+                && !is_bot(voice_state)
+        })
+        .count();
+    Some(VoicePresence {
+        channel_id,
+        member_count,
+    })
+}
+
+/// Pending timers that leave the voice channel of a guild since the bot is alone there, see
+/// [`crate::FrameworkOptions::voice_auto_leave`]. Only contains guilds in which the bot is alone
+#[cfg(feature = "voice")]
+#[derive(Default)]
+pub(crate) struct VoiceAutoLeave(
+    std::sync::Mutex<std::collections::HashMap<serenity::GuildId, tokio::task::JoinHandle<()>>>,
+);
+
+#[cfg(feature = "voice")]
+impl VoiceAutoLeave {
+    /// Starts the auto leave timer if the bot is alone in its voice channel in the given guild, or
+    /// cancels it otherwise. Called after the guild's voice states in the cache have changed
+    pub(crate) fn update(
+        &self,
+        ctx: &serenity::Context,
+        guild_id: serenity::GuildId,
+        timeout: std::time::Duration,
+    ) {
+        let is_alone =
+            matches!(voice_presence(ctx, guild_id), Some(presence) if presence.member_count == 0);
+
+        let mut timers = self.0.lock().unwrap();
+        if !is_alone {
+            if let Some(timer) = timers.remove(&guild_id) {
+                timer.abort();
+            }
+        } else if let std::collections::hash_map::Entry::Vacant(entry) = timers.entry(guild_id) {
+            let ctx = ctx.clone();
+            entry.insert(tokio::spawn(async move {
+                tokio::time::sleep(timeout).await;
+                if let Some(songbird) = songbird::get(&ctx).await {
+                    // Fails if the bot has already left in the meantime, which is fine
+                    let _: Result<_, _> = songbird.remove(guild_id).await;
+                }
+            }));
+        }
+    }
+
+    /// Cancels the timer of the given guild, e.g. because the bot was removed from it
+    pub(crate) fn remove_guild(&self, guild_id: serenity::GuildId) {
+        if let Some(timer) = self.0.lock().unwrap().remove(&guild_id) {
+            timer.abort();
+        }
+    }
+}
+
+#[test]
+fn test_voice_presence() {
+    use serenity::json::prelude::*;
+
+    let bot_id = serenity::UserId(99);
+    let mut voice_states = std::collections::HashMap::new();
+    let mut set_channel = |user_id: u64, channel_id: Option<u64>| {
+        let voice_state: serenity::VoiceState = from_value(serenity::json::json!({
+            "guild_id": serenity::GuildId(10),
+            "channel_id": channel_id.map(serenity::ChannelId),
+            "user_id": serenity::UserId(user_id),
+            "session_id": "",
+            "deaf": false,
+            "mute": false,
+            "self_deaf": false,
+            "self_mute": false,
+            "self_video": false,
+            "suppress": false,
+        }))
+        .unwrap();
+        voice_states.insert(serenity::UserId(user_id), voice_state);
+        // User 2 is a bot
+        presence_from_voice_states(&voice_states, bot_id, |voice_state| {
+            voice_state.user_id == serenity::UserId(2)
+        })
+    };
+
+    assert_eq!(set_channel(1, Some(100)), None);
+
+    set_channel(99, Some(100));
+    set_channel(2, Some(100));
+    assert_eq!(
+        set_channel(3, Some(200)),
+        Some(VoicePresence {
+            channel_id: serenity::ChannelId(100),
+            member_count: 1,
+        })
+    );
+
+    // Users moving to the bot's channel and disconnecting
+    assert_eq!(set_channel(3, Some(100)).map(|p| p.member_count), Some(2));
+    set_channel(1, None);
+    assert_eq!(set_channel(3, None).map(|p| p.member_count), Some(0));
+
+    assert_eq!(set_channel(99, None), None);
+}
@@ -0,0 +1,451 @@
+//! Guild-scoped music queue on top of songbird's [`songbird::tracks::TrackQueue`], see
+//! [`MusicQueue`]
+
+use crate::serenity_prelude as serenity;
+use std::sync::{Arc, Mutex, Weak};
+
+/// Which tracks of a [`MusicQueue`] are repeated
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LoopMode {
+    /// Every track is played once
+    Off,
+    /// The current track is repeated until it's skipped
+    Track,
+    /// Finished tracks are added to the end of the queue again.
+    ///
+    /// Tracks are recreated from their source URL with youtube-dl, so this only works for tracks
+    /// that have one in their metadata, like those created with [`songbird::ytdl`]
+    Queue,
+}
+
+#[allow(clippy::derivable_impls)] // #[default] on enum variants requires Rust 1.62
+impl Default for LoopMode {
+    fn default() -> Self {
+        Self::Off
+    }
+}
+
+/// Messages from the music queues to the framework. They're handled in a task spawned by
+/// [`crate::Framework::start`], so that no work is done inside songbird's event callbacks
+pub(crate) enum QueueMessage {
+    /// A track notification to dispatch to the listener, with the serenity context to dispatch it
+    /// with
+    Event(serenity::Context, Box<crate::Event<'static>>),
+    /// Add this finished track to the end of the queue again, see [`LoopMode::Queue`]
+    Requeue(MusicQueue, songbird::tracks::TrackHandle),
+    /// The queue has disconnected after being idle, so it can be dropped
+    Disconnected(MusicQueue),
+}
+
+/// Framework-wide storage of the music queues of all guilds
+pub(crate) struct MusicQueues {
+    /// The music queue of every guild which used one so far
+    queues: Mutex<std::collections::HashMap<serenity::GuildId, MusicQueue>>,
+    /// Queues send their track notifications and other [`QueueMessage`]s here
+    event_sender: tokio::sync::mpsc::UnboundedSender<QueueMessage>,
+    /// Receiving end of `event_sender`. Taken out once the framework starts
+    event_receiver: Mutex<Option<tokio::sync::mpsc::UnboundedReceiver<QueueMessage>>>,
+}
+
+impl Default for MusicQueues {
+    fn default() -> Self {
+        let (event_sender, event_receiver) = tokio::sync::mpsc::unbounded_channel();
+        Self {
+            queues: Mutex::new(std::collections::HashMap::new()),
+            event_sender,
+            event_receiver: Mutex::new(Some(event_receiver)),
+        }
+    }
+}
+
+impl MusicQueues {
+    /// Returns the music queue of the given guild, creating it if it doesn't exist yet
+    pub(crate) fn get_or_create(
+        &self,
+        discord: &serenity::Context,
+        songbird: Arc<songbird::Songbird>,
+        guild_id: serenity::GuildId,
+        idle_timeout: Option<std::time::Duration>,
+    ) -> MusicQueue {
+        self.queues
+            .lock()
+            .unwrap()
+            .entry(guild_id)
+            .or_insert_with(|| {
+                MusicQueue(Arc::new(QueueState {
+                    guild_id,
+                    tracks: songbird::tracks::TrackQueue::new(),
+                    loop_mode: Mutex::new(LoopMode::default()),
+                    announced_track: Mutex::new(None),
+                    idle_timeout,
+                    idle_disconnect: Mutex::new(None),
+                    songbird,
+                    discord: discord.clone(),
+                    event_sender: self.event_sender.clone(),
+                }))
+            })
+            .clone()
+    }
+
+    /// Takes out the receiver of [`QueueMessage`]s. Returns None if it was already taken
+    pub(crate) fn take_event_receiver(
+        &self,
+    ) -> Option<tokio::sync::mpsc::UnboundedReceiver<QueueMessage>> {
+        self.event_receiver.lock().unwrap().take()
+    }
+
+    /// Drops the given queue, unless it has already been replaced by a new one
+    pub(crate) fn remove(&self, queue: &MusicQueue) {
+        let mut queues = self.queues.lock().unwrap();
+        if matches!(queues.get(&queue.0.guild_id), Some(x) if Arc::ptr_eq(&x.0, &queue.0)) {
+            queues.remove(&queue.0.guild_id);
+        }
+    }
+
+    /// Drops the queue of the given guild, e.g. because the bot was removed from it
+    pub(crate) fn remove_guild(&self, guild_id: serenity::GuildId) {
+        self.queues.lock().unwrap().remove(&guild_id);
+    }
+}
+
+/// Shared state of a [`MusicQueue`]
+struct QueueState {
+    /// The guild this queue plays in
+    guild_id: serenity::GuildId,
+    /// The underlying songbird queue
+    tracks: songbird::tracks::TrackQueue,
+    /// Current loop mode
+    loop_mode: Mutex<LoopMode>,
+    /// The track which [`crate::Event::TrackStart`] was last emitted for. Used to tell a track
+    /// starting apart from a track resuming, since songbird reports both as playing
+    announced_track: Mutex<Option<songbird::tracks::TrackHandle>>,
+    /// How long to stay connected after the queue has run empty
+    idle_timeout: Option<std::time::Duration>,
+    /// Background task that disconnects once the idle timeout has passed
+    idle_disconnect: Mutex<Option<tokio::task::JoinHandle<()>>>,
+    /// Songbird manager to disconnect with
+    songbird: Arc<songbird::Songbird>,
+    /// Serenity context to dispatch track notifications with
+    discord: serenity::Context,
+    /// Where to send track notifications and other messages to, see [`MusicQueues`]
+    event_sender: tokio::sync::mpsc::UnboundedSender<QueueMessage>,
+}
+
+/// A guild's music queue, which plays the enqueued tracks one after another in the guild's voice
+/// call.
+///
+/// Wraps songbird's [`songbird::tracks::TrackQueue`] and additionally
+/// - emits [`crate::Event::TrackStart`] and [`crate::Event::TrackEnd`] to the
+///   [listener](crate::FrameworkOptions::listener)
+/// - supports shuffling and [loop modes](LoopMode)
+/// - disconnects from the voice channel once the queue has been empty for
+///   [`crate::FrameworkOptions::music_idle_timeout`]
+///
+/// Obtained with [`crate::Context::music_queue`]. This is a cheap handle; clones refer to the
+/// same queue. The framework forgets about the queue once it has disconnected after being idle,
+/// or when the bot is removed from the guild; the next call to `music_queue` then creates a new
+/// one.
+///
+/// ```rust,no_run
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// /// Plays a song from the given URL
+/// #[poise::command(slash_command, requires_voice)]
+/// async fn play(
+///     ctx: poise::Context<'_, (), Error>,
+///     #[description = "URL of the song"] url: String,
+/// ) -> Result<(), Error> {
+///     ctx.join_author_channel().await?;
+///     let queue = match ctx.music_queue().await {
+///         Some(x) => x,
+///         None => return Ok(()),
+///     };
+///
+///     let source = poise::songbird::ytdl(&url).await?;
+///     queue.enqueue(source).await?;
+///     ctx.say(format!("Added to the queue at position {}", queue.len())).await?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct MusicQueue(Arc<QueueState>);
+
+impl std::fmt::Debug for MusicQueue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MusicQueue")
+            .field("guild_id", &self.0.guild_id)
+            .field("len", &self.0.tracks.len())
+            .field("loop_mode", &self.loop_mode())
+            .finish()
+    }
+}
+
+impl MusicQueue {
+    /// Adds a track to the end of the queue. If the queue was empty, it starts playing right away.
+    ///
+    /// Fails with [`songbird::error::JoinError::NoCall`] if the bot isn't in a voice channel of
+    /// this guild, see [`crate::Context::join_author_channel`]
+    pub async fn enqueue(
+        &self,
+        source: songbird::input::Input,
+    ) -> Result<songbird::tracks::TrackHandle, songbird::error::JoinError> {
+        enqueue(&self.0, source).await
+    }
+
+    /// Skips the current track, so that the next one starts playing
+    pub fn skip(&self) -> songbird::tracks::TrackResult<()> {
+        self.0.tracks.skip()
+    }
+
+    /// Pauses the current track
+    pub fn pause(&self) -> songbird::tracks::TrackResult<()> {
+        self.0.tracks.pause()
+    }
+
+    /// Resumes the current track if it was paused
+    pub fn resume(&self) -> songbird::tracks::TrackResult<()> {
+        self.0.tracks.resume()
+    }
+
+    /// Stops the current track and removes all tracks from the queue
+    pub fn stop(&self) {
+        self.0.tracks.stop();
+    }
+
+    /// Randomly reorders the upcoming tracks. The current track keeps playing
+    pub fn shuffle(&self) {
+        self.0.tracks.modify_queue(shuffle_upcoming);
+    }
+
+    /// Returns the current loop mode
+    pub fn loop_mode(&self) -> LoopMode {
+        *self.0.loop_mode.lock().unwrap()
+    }
+
+    /// Changes the loop mode. Applies to the current track as well
+    pub fn set_loop_mode(&self, loop_mode: LoopMode) {
+        *self.0.loop_mode.lock().unwrap() = loop_mode;
+        if let Some(track) = self.0.tracks.current() {
+            let _: Result<_, _> = match loop_mode {
+                LoopMode::Track => track.enable_loop(),
+                LoopMode::Off | LoopMode::Queue => track.disable_loop(),
+            };
+        }
+    }
+
+    /// Returns the track that is currently playing (or paused)
+    pub fn now_playing(&self) -> Option<songbird::tracks::TrackHandle> {
+        self.0.tracks.current()
+    }
+
+    /// Returns all tracks in the queue, starting with the current track
+    pub fn tracks(&self) -> Vec<songbird::tracks::TrackHandle> {
+        self.0.tracks.current_queue()
+    }
+
+    /// Returns the number of tracks in the queue, including the current track
+    pub fn len(&self) -> usize {
+        self.0.tracks.len()
+    }
+
+    /// Returns whether the queue is empty, i.e. nothing is playing
+    pub fn is_empty(&self) -> bool {
+        self.0.tracks.is_empty()
+    }
+
+    /// Returns the guild this queue plays in
+    pub fn guild_id(&self) -> serenity::GuildId {
+        self.0.guild_id
+    }
+
+    /// Recreates a finished track from its source URL and adds it to the end of the queue, see
+    /// [`LoopMode::Queue`]
+    pub(crate) async fn requeue(&self, track: &songbird::tracks::TrackHandle) {
+        if let Some(source_url) = &track.metadata().source_url {
+            if let Ok(source) = songbird::ytdl(source_url).await {
+                let _: Result<_, _> = enqueue(&self.0, source).await;
+            }
+        }
+    }
+}
+
+/// Adds a track to the queue and registers the event handlers for track notifications
+async fn enqueue(
+    state: &Arc<QueueState>,
+    source: songbird::input::Input,
+) -> Result<songbird::tracks::TrackHandle, songbird::error::JoinError> {
+    let call = state
+        .songbird
+        .get(state.guild_id)
+        .ok_or(songbird::error::JoinError::NoCall)?;
+    let track = state.tracks.add_source(source, &mut *call.lock().await);
+
+    for &kind in &[songbird::TrackEvent::Play, songbird::TrackEvent::End] {
+        let handler = TrackEventHandler {
+            queue: Arc::downgrade(state),
+            track: track.clone(),
+            kind,
+        };
+        let _: Result<_, _> = track.add_event(songbird::Event::Track(kind), handler);
+    }
+
+    cancel_idle_disconnect(state);
+    // songbird doesn't report the first track of a queue as playing, since it was never paused
+    if state.tracks.len() == 1 {
+        track_started(state, &track);
+    }
+
+    Ok(track)
+}
+
+/// Emits [`crate::Event::TrackStart`] unless the track is merely resuming, and applies the loop
+/// mode to the new track
+fn track_started(state: &QueueState, track: &songbird::tracks::TrackHandle) {
+    {
+        let mut announced_track = state.announced_track.lock().unwrap();
+        if let Some(announced_track) = &*announced_track {
+            if announced_track.uuid() == track.uuid() {
+                return;
+            }
+        }
+        *announced_track = Some(track.clone());
+    }
+
+    if *state.loop_mode.lock().unwrap() == LoopMode::Track {
+        let _: Result<_, _> = track.enable_loop();
+    }
+
+    emit(
+        state,
+        crate::Event::TrackStart {
+            guild_id: state.guild_id,
+            track: track.clone(),
+        },
+    );
+}
+
+/// Emits [`crate::Event::TrackEnd`], requeues the track in [`LoopMode::Queue`] and starts the
+/// idle timeout if nothing is left to play
+fn track_ended(state: &Arc<QueueState>, track: &songbird::tracks::TrackHandle) {
+    emit(
+        state,
+        crate::Event::TrackEnd {
+            guild_id: state.guild_id,
+            track: track.clone(),
+        },
+    );
+
+    if *state.loop_mode.lock().unwrap() == LoopMode::Queue {
+        // Requeueing runs youtube-dl and locks the call, which must not happen inside songbird's
+        // event callback. If it succeeds, it cancels the idle timeout started below
+        let message = QueueMessage::Requeue(MusicQueue(state.clone()), track.clone());
+        let _: Result<_, _> = state.event_sender.send(message);
+    }
+
+    // songbird may or may not have removed the ended track from the queue yet
+    let is_idle = state
+        .tracks
+        .current_queue()
+        .iter()
+        .all(|queued| queued.uuid() == track.uuid());
+    if is_idle {
+        start_idle_disconnect(state);
+    }
+}
+
+/// Sends a track notification to the framework, which dispatches it to the listener
+fn emit(state: &QueueState, event: crate::Event<'static>) {
+    // If the receiver is gone, the framework has shut down and nobody is listening anymore
+    let message = QueueMessage::Event(state.discord.clone(), Box::new(event));
+    let _: Result<_, _> = state.event_sender.send(message);
+}
+
+/// Disconnects from the voice channel after the idle timeout, unless a track is enqueued
+/// in the meantime
+fn start_idle_disconnect(state: &Arc<QueueState>) {
+    let idle_timeout = match state.idle_timeout {
+        Some(x) => x,
+        None => return,
+    };
+
+    let queue = Arc::downgrade(state);
+    let task = tokio::spawn(async move {
+        tokio::time::sleep(idle_timeout).await;
+        if let Some(state) = queue.upgrade() {
+            // Fails if the bot has already left on its own, which is fine
+            let _: Result<_, _> = state.songbird.remove(state.guild_id).await;
+            let message = QueueMessage::Disconnected(MusicQueue(state.clone()));
+            let _: Result<_, _> = state.event_sender.send(message);
+        }
+    });
+    if let Some(previous_task) = state.idle_disconnect.lock().unwrap().replace(task) {
+        previous_task.abort();
+    }
+}
+
+/// Cancels a pending idle disconnect, see [`start_idle_disconnect`]
+fn cancel_idle_disconnect(state: &QueueState) {
+    if let Some(task) = state.idle_disconnect.lock().unwrap().take() {
+        task.abort();
+    }
+}
+
+/// Songbird event handler which forwards track events of a single track to its [`MusicQueue`]
+struct TrackEventHandler {
+    /// The queue which the track belongs to. Weak, because the queue owns the track which owns
+    /// this handler
+    queue: Weak<QueueState>,
+    /// The track this handler was registered on
+    track: songbird::tracks::TrackHandle,
+    /// Which event this handler was registered for
+    kind: songbird::TrackEvent,
+}
+
+#[async_trait::async_trait]
+impl songbird::EventHandler for TrackEventHandler {
+    async fn act(&self, _: &songbird::EventContext<'_>) -> Option<songbird::Event> {
+        let state = match self.queue.upgrade() {
+            Some(x) => x,
+            None => return Some(songbird::Event::Cancel),
+        };
+
+        match self.kind {
+            songbird::TrackEvent::Play => track_started(&state, &self.track),
+            songbird::TrackEvent::End => track_ended(&state, &self.track),
+            _ => {}
+        }
+        None
+    }
+}
+
+/// Shuffles all elements except for the first one (the current track) with Fisher-Yates
+fn shuffle_upcoming<T>(items: &mut std::collections::VecDeque<T>) {
+    use std::hash::{BuildHasher, Hasher};
+
+    // Every RandomState is randomly seeded, which is good enough for shuffling and saves a
+    // dependency on rand
+    let random_state = std::collections::hash_map::RandomState::new();
+    for i in (2..items.len()).rev() {
+        let mut hasher = random_state.build_hasher();
+        hasher.write_usize(i);
+        let j = 1 + (hasher.finish() % i as u64) as usize;
+        items.swap(i, j);
+    }
+}
+
+#[test]
+fn test_shuffle_upcoming() {
+    let original = (0..50).collect::<std::collections::VecDeque<_>>();
+
+    let mut items = original.clone();
+    shuffle_upcoming(&mut items);
+    assert_eq!(items[0], 0);
+    let mut sorted = items.iter().copied().collect::<Vec<_>>();
+    sorted.sort_unstable();
+    assert_eq!(sorted, original.iter().copied().collect::<Vec<_>>());
+    // The odds of 49 elements staying in place are negligible
+    assert_ne!(items, original);
+
+    let mut items = std::collections::VecDeque::from(vec![1, 2]);
+    shuffle_upcoming(&mut items);
+    assert_eq!(items, [1, 2]);
+}